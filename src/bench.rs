@@ -0,0 +1,170 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::ParseError;
+use crate::Solver;
+use serde::Serialize;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Timings {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub max_ns: u64,
+}
+
+impl Timings {
+    pub fn from_durations(durations: &[Duration]) -> Timings {
+        let mut nanos: Vec<u64> = durations.iter().map(|d| d.as_nanos() as u64).collect();
+        nanos.sort_unstable();
+        let len = nanos.len();
+        Timings {
+            min_ns: nanos[0],
+            median_ns: (nanos[(len - 1) / 2] + nanos[len / 2]) / 2,
+            max_ns: nanos[nanos.len() - 1],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct DayBench {
+    pub day: u32,
+    pub iterations: usize,
+    pub parse: Timings,
+    pub part1: Timings,
+    pub part2: Timings,
+}
+
+pub fn bench<S: Solver>(day: u32, text: &str, iterations: usize) -> Result<DayBench, ParseError> {
+    assert!(iterations > 0);
+    let mut parse = Vec::with_capacity(iterations);
+    let mut part1 = Vec::with_capacity(iterations);
+    let mut part2 = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let input = black_box(S::parse(black_box(text))?);
+        parse.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(S::part1(&input));
+        part1.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(S::part2(&input));
+        part2.push(start.elapsed());
+    }
+
+    Ok(DayBench {
+        day,
+        iterations,
+        parse: Timings::from_durations(&parse),
+        part1: Timings::from_durations(&part1),
+        part2: Timings::from_durations(&part2),
+    })
+}
+
+fn format_nanos(nanos: u64) -> String {
+    if nanos >= 1_000_000_000 {
+        format!("{:.2} s", nanos as f64 / 1e9)
+    } else if nanos >= 1_000_000 {
+        format!("{:.2} ms", nanos as f64 / 1e6)
+    } else if nanos >= 1_000 {
+        format!("{:.2} µs", nanos as f64 / 1e3)
+    } else {
+        format!("{} ns", nanos)
+    }
+}
+
+pub fn format_table(results: &[DayBench]) -> String {
+    let mut table = format!(
+        "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}\n",
+        "Day", "Step", "Min", "Median", "Max"
+    );
+    for result in results {
+        let steps = [
+            ("parse", &result.parse),
+            ("part1", &result.part1),
+            ("part2", &result.part2),
+        ];
+        for (step, timings) in steps.iter() {
+            table += &format!(
+                "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}\n",
+                result.day,
+                step,
+                format_nanos(timings.min_ns),
+                format_nanos(timings.median_ns),
+                format_nanos(timings.max_ns)
+            );
+        }
+    }
+    table
+}
+
+pub fn format_json(results: &[DayBench]) -> String {
+    serde_json::to_string_pretty(results).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timings() {
+        let durations = [
+            Duration::from_nanos(30),
+            Duration::from_nanos(10),
+            Duration::from_nanos(20),
+        ];
+        let timings = Timings::from_durations(&durations);
+        assert_eq!(timings.min_ns, 10);
+        assert_eq!(timings.median_ns, 20);
+        assert_eq!(timings.max_ns, 30);
+
+        let timings = Timings::from_durations(&durations[..2]);
+        assert_eq!(timings.median_ns, 20);
+    }
+
+    #[test]
+    fn test_format_nanos() {
+        assert_eq!(format_nanos(999), "999 ns");
+        assert_eq!(format_nanos(1_500), "1.50 µs");
+        assert_eq!(format_nanos(2_250_000), "2.25 ms");
+        assert_eq!(format_nanos(3_000_000_000), "3.00 s");
+    }
+
+    #[test]
+    fn test_format_json() {
+        let timings = Timings {
+            min_ns: 1,
+            median_ns: 2,
+            max_ns: 3,
+        };
+        let result = DayBench {
+            day: 3,
+            iterations: 5,
+            parse: timings,
+            part1: timings,
+            part2: timings,
+        };
+        let json: serde_json::Value = serde_json::from_str(&format_json(&[result])).unwrap();
+        assert_eq!(json[0]["day"], 3);
+        assert_eq!(json[0]["iterations"], 5);
+        assert_eq!(json[0]["part2"]["median_ns"], 2);
+    }
+}
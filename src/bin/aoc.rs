@@ -0,0 +1,45 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use advent_of_code_2020::runner;
+use advent_of_code_2020::runner::Options;
+
+const USAGE: &str = "\
+Usage:
+    aoc run <day> [--part <1|2>] [--input <path|->]
+    aoc run --all [--part <1|2>]
+    aoc bench <day>|--all [--iterations <n>] [--json]
+    aoc verify [<day>]
+
+Inputs default to $AOC_INPUT_DIR/input-day-NN.txt, falling back to data/.
+Known answers are read from answers.toml in the same directory.";
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+
+    let result = match args.next().as_deref() {
+        Some("run") => Options::parse(args).and_then(|options| runner::run(&options)),
+        Some("bench") => Options::parse(args).and_then(|options| runner::bench(&options)),
+        Some("verify") => Options::parse(args).and_then(|options| runner::verify(&options)),
+        _ => Err(String::from(USAGE)),
+    };
+
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
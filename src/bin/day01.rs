@@ -15,30 +15,8 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-fn main() {
-    println!("Part 1");
-    let text = include_str!("../../data/input-day-01.txt");
-
-    let numbers: Vec<i32> = text.lines().map(|line| line.parse().unwrap()).collect();
+use advent_of_code_2020::runner;
 
-    for num1 in numbers.clone() {
-        for num2 in numbers.clone() {
-            if num1 + num2 == 2020 {
-                println!("{} + {} = 2020", num1, num2);
-                println!("{} * {} = {}", num1, num2, num1 * num2);
-            }
-        }
-    }
-
-    println!("Part 2");
-    for num1 in numbers.clone() {
-        for num2 in numbers.clone() {
-            for num3 in numbers.clone() {
-                if num1 + num2 + num3 == 2020 {
-                    println!("{} + {} + {} = 2020", num1, num2, num3);
-                    println!("{} * {} * {} = {}", num1, num2, num3, num1 * num2 * num3);
-                }
-            }
-        }
-    }
+fn main() {
+    runner::day_main(1);
 }
@@ -15,31 +15,106 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2020::day03::*;
+use advent_of_code_2020::day03::{Map, Point, SLOPES};
+use advent_of_code_2020::runner;
+use advent_of_code_2020::runner::Options;
 
-fn main() {
-    let text = include_str!("../../data/input-day-03.txt");
+const DEFAULT_MAX_RIGHT: usize = 10;
+const DEFAULT_MAX_DOWN: usize = 10;
+
+fn read_map(remaining: Vec<String>) -> Result<Map, String> {
+    let mut options = Options::parse(remaining)?;
+    options.day = Some(3);
+    let text = runner::read_input(runner::find_day(3).unwrap(), &options)?;
+    Map::from_text(text.as_str()).map_err(|error| format!("Invalid input for day 3: {}", error))
+}
+
+fn parse_slope(value: &str) -> Option<Point> {
+    let (right, down) = value.split_once(',')?;
+    let down = down.parse().ok().filter(|&down| down > 0)?;
+    Some(Point {
+        x: down,
+        y: right.parse().ok()?,
+    })
+}
 
-    println!("Part 1");
+fn render(args: Vec<String>) -> Result<(), String> {
+    let mut ansi = false;
+    let mut slopes = vec![];
+    let mut remaining = vec![];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--render" => {}
+            "--ansi" => ansi = true,
+            "--slope" => slopes.push(
+                args.next()
+                    .as_deref()
+                    .and_then(parse_slope)
+                    .ok_or("--slope requires <right>,<down> with down > 0")?,
+            ),
+            _ => remaining.push(arg),
+        }
+    }
+    if slopes.is_empty() {
+        slopes = SLOPES.to_vec();
+    }
 
-    let map = Map::from_text(text);
-    let map = map.auto_extend(Point { x: 1, y: 3 });
-    let count = map.traverse(Point { x: 1, y: 3 });
+    let map = read_map(remaining)?;
+    if ansi {
+        print!("{}", map.render_ansi(&slopes));
+    } else {
+        print!("{}", map.render(&slopes));
+    }
+    Ok(())
+}
 
-    println!("Trees encountered: {}", count);
+fn search(args: Vec<String>) -> Result<(), String> {
+    let mut max_right = DEFAULT_MAX_RIGHT;
+    let mut max_down = DEFAULT_MAX_DOWN;
+    let mut remaining = vec![];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--search" => {}
+            "--max-right" | "--max-down" => {
+                let value = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .ok_or(format!("{} requires a number", arg))?;
+                if arg == "--max-right" {
+                    max_right = value;
+                } else {
+                    max_down = value;
+                }
+            }
+            _ => remaining.push(arg),
+        }
+    }
 
-    println!("Part 2");
+    let map = read_map(remaining)?;
+    for (direction, trees) in map.search_slopes(max_right, max_down) {
+        println!(
+            "right {}, down {}: {} trees",
+            direction.y, direction.x, trees
+        );
+    }
+    Ok(())
+}
 
-    let map = Map::from_text(text);
-    let map = map.auto_extend(Point { x: 1, y: 7 });
-    let count1 = map.traverse(Point { x: 1, y: 1 });
-    let count2 = map.traverse(Point { x: 1, y: 3 });
-    let count3 = map.traverse(Point { x: 1, y: 5 });
-    let count4 = map.traverse(Point { x: 1, y: 7 });
-    let count5 = map.traverse(Point { x: 2, y: 1 });
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = if args.iter().any(|arg| arg == "--render") {
+        render(args)
+    } else if args.iter().any(|arg| arg == "--search") {
+        search(args)
+    } else {
+        runner::day_main(3);
+        return;
+    };
 
-    println!(
-        "Multiplied trees encountered: {}",
-        count1 * count2 * count3 * count4 * count5
-    );
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
 }
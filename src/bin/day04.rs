@@ -15,28 +15,54 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2020::day04::Passport;
+use advent_of_code_2020::day04::convert::Format;
+use advent_of_code_2020::runner;
+use advent_of_code_2020::runner::Options;
 
-fn main() {
-    let text = include_str!("../../data/input-day-04.txt");
-
-    println!("Part 1");
-
-    let passport_list = Passport::parse_text(text);
+const USAGE: &str = "\
+Usage:
+    day04 [--part <1|2>] [--input <path|->]
+    day04 convert --to <json|csv|batch> [--from <batch|json|csv>] [--input <path|->]";
 
-    let count = passport_list
-        .iter()
-        .filter(|passport| passport.fields_valid())
-        .count();
+fn parse_format(value: Option<String>) -> Result<Format, String> {
+    let value = value.ok_or("Missing format")?;
+    Format::parse(&value).ok_or(format!("Unknown format '{}'", value))
+}
 
-    println!("Passports with valid fields: {}", count);
+fn convert(args: Vec<String>) -> Result<(), String> {
+    let mut from = Format::Batch;
+    let mut to = None;
+    let mut remaining = vec![];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => from = parse_format(args.next())?,
+            "--to" => to = Some(parse_format(args.next())?),
+            _ => remaining.push(arg),
+        }
+    }
+    let to = to.ok_or("Missing --to <format>")?;
 
-    println!("Part 2");
+    let mut options = Options::parse(remaining)?;
+    options.day = Some(4);
+    let text = runner::read_input(runner::find_day(4).unwrap(), &options)?;
+    let passports = from
+        .read(&text)
+        .map_err(|error| format!("Invalid input for day 4: {}", error))?;
+    print!("{}", to.write(&passports));
+    Ok(())
+}
 
-    let count = passport_list
-        .iter()
-        .filter(|passport| passport.data_valid())
-        .count();
+fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) != Some("convert") {
+        runner::day_main(4);
+        return;
+    }
 
-    println!("Passports with valid data: {}", count);
+    if let Err(error) = convert(args.skip(1).collect()) {
+        eprintln!("{}", error);
+        eprintln!("{}", USAGE);
+        std::process::exit(1);
+    }
 }
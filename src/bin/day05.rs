@@ -15,41 +15,8 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2020::day05::*;
+use advent_of_code_2020::runner;
 
 fn main() {
-    let text = include_str!("../../data/input-day-05.txt");
-
-    println!("Part 1");
-
-    let seat_numbers: Vec<SeatNumber> = text.lines().map(|line| SeatNumber::from(line)).collect();
-
-    let highest_seat_id = seat_numbers
-        .iter()
-        .map(|sn| sn.get_seat_id())
-        .max()
-        .unwrap();
-
-    println!("Highest seat id: {}", highest_seat_id);
-
-    println!("Part 2");
-
-    for seat_left in seat_numbers.iter() {
-        let your_seat = seat_numbers
-            .iter()
-            .find(|seat| seat.get_seat_id() == seat_left.get_seat_id() + 1);
-        if your_seat.is_none() {
-            let seat_right = seat_numbers
-                .iter()
-                .find(|seat| seat.get_seat_id() == seat_left.get_seat_id() + 2);
-            if let Some(seat_right) = seat_right {
-                println!(
-                    "Seat {} and {} are taken, so your seat is: {}",
-                    seat_left.get_seat_id(),
-                    seat_right.get_seat_id(),
-                    seat_left.get_seat_id() + 1
-                )
-            }
-        }
-    }
+    runner::day_main(5);
 }
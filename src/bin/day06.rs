@@ -15,26 +15,35 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2020::day06::DeclarationForm;
+use advent_of_code_2020::day06;
+use advent_of_code_2020::day06::stats;
+use advent_of_code_2020::runner;
+use advent_of_code_2020::runner::Options;
+
+fn print_stats(args: Vec<String>) -> Result<(), String> {
+    let options = Options::parse(args)?;
+    let text = runner::read_input(runner::find_day(6).unwrap(), &options)?;
+    let groups =
+        day06::parse(&text).map_err(|error| format!("Invalid input for day 6: {}", error))?;
+
+    let statistics = stats::statistics(&groups);
+    if options.json {
+        println!("{}", stats::format_json(&statistics));
+    } else {
+        print!("{}", stats::format_table(&statistics));
+    }
+    Ok(())
+}
 
 fn main() {
-    let text = include_str!("../../data/input-day-06.txt");
-
-    println!("Part 1");
-
-    let form_list = DeclarationForm::parse_part1(text);
-
-    let sum = form_list
-        .iter()
-        .fold(0, |sum, form| form.answers.len() + sum);
-
-    println!("Sum of counts: {}", sum);
-
-    println!("Part 2");
-
-    let form_list = DeclarationForm::parse_part2(text);
-
-    let sum: usize = form_list.iter().map(|form| form.answers.len()).sum();
-
-    println!("Sum of counts: {}", sum);
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) != Some("stats") {
+        runner::day_main(6);
+        return;
+    }
+
+    if let Err(error) = print_stats(args.skip(1).collect()) {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
 }
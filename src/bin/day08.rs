@@ -15,27 +15,85 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2020::day08::*;
+use advent_of_code_2020::day08;
+use advent_of_code_2020::day08::cfg::ControlFlowGraph;
+use advent_of_code_2020::day08::debugger::Debugger;
+use advent_of_code_2020::day08::Instruction;
+use advent_of_code_2020::runner;
+use std::io::{BufRead, Write};
 
-fn main() {
-    let text = include_str!("../../data/input-day-08.txt");
-
-    println!("Part 1");
+const USAGE: &str = "\
+Usage:
+    day08 [--part <1|2>] [--input <path|->]
+    day08 debug <file>
+    day08 cfg <file>";
 
-    let program = Instruction::parse(text);
+fn read_program(args: Vec<String>) -> Result<Vec<Instruction>, String> {
+    let path = match args.as_slice() {
+        [path] => path,
+        _ => return Err(String::from("Expected a single program file")),
+    };
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {}", path, error))?;
+    day08::assemble(&text).map_err(|error| format!("Invalid program {}: {}", path, error))
+}
 
-    let (acc, _completed) = execute_once(&program);
+fn print_cfg(args: Vec<String>) -> Result<(), String> {
+    let program = read_program(args)?;
+    print!("{}", ControlFlowGraph::new(&program).to_dot());
+    Ok(())
+}
 
-    println!("accumulator: {}", acc);
+fn debug(args: Vec<String>) -> Result<(), String> {
+    let program = read_program(args)?;
 
-    println!("Part 2");
+    let mut debugger = Debugger::new(&program);
+    println!(
+        "{} instructions loaded, type 'help' for commands",
+        program.len()
+    );
+    let stdin = std::io::stdin();
+    let mut last_command = String::new();
+    loop {
+        print!("(debug) ");
+        std::io::stdout().flush().unwrap();
+        let mut line = String::new();
+        // Input that can't be read, like invalid UTF-8, ends the session like EOF
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            println!();
+            return Ok(());
+        }
 
-    let mutated_programs = MutatedPrograms::new(program);
+        // An empty line repeats the previous command
+        let command = match line.trim() {
+            "" => last_command.clone(),
+            command => command.to_string(),
+        };
+        if command == "q" || command == "quit" {
+            return Ok(());
+        }
+        match debugger.execute(&command) {
+            Ok(output) => println!("{}", output),
+            Err(error) => println!("{}", error),
+        }
+        last_command = command;
+    }
+}
 
-    for mutated_program in mutated_programs {
-        let (acc, completed) = execute_once(&mutated_program);
-        if completed {
-            println!("accumulator: {}", acc);
+fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    let command: fn(Vec<String>) -> Result<(), String> = match args.peek().map(String::as_str) {
+        Some("debug") => debug,
+        Some("cfg") => print_cfg,
+        _ => {
+            runner::day_main(8);
+            return;
         }
+    };
+
+    if let Err(error) = command(args.skip(1).collect()) {
+        eprintln!("{}", error);
+        eprintln!("{}", USAGE);
+        std::process::exit(1);
     }
 }
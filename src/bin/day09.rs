@@ -15,22 +15,8 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2020::day09::*;
+use advent_of_code_2020::runner;
 
 fn main() {
-    let text = include_str!("../../data/input-day-09.txt");
-
-    println!("Part 1");
-
-    let numbers = parse(text);
-
-    let weakness = find_weakness(25, &numbers).unwrap();
-
-    println!("Weakness: {}", weakness);
-
-    println!("Part 2");
-
-    let exploit = exploit_weakness(weakness, &numbers).unwrap();
-
-    println!("Exploit: {}", exploit);
+    runner::day_main(9);
 }
@@ -15,23 +15,8 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2020::day10::*;
+use advent_of_code_2020::runner;
 
 fn main() {
-    let text = include_str!("../../data/input-day-10.txt");
-
-    println!("Part 1");
-
-    let numbers = parse(text);
-    let diff_map = find_diffs(&numbers);
-    let multiplied = *diff_map.get(&1).unwrap() * *diff_map.get(&3).unwrap();
-    assert_eq!(diff_map.get(&2), None);
-
-    println!("Multiplied differences: {}", multiplied);
-
-    println!("Part 2");
-
-    let distinct_arrangements = find_distinct_arrangements(&numbers, None);
-
-    println!("distinct_arrangements: {}", distinct_arrangements);
+    runner::day_main(10);
 }
@@ -15,25 +15,8 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2020::day11::*;
+use advent_of_code_2020::runner;
 
 fn main() {
-    let text = include_str!("../../data/input-day-11.txt");
-
-    println!("Part 1");
-
-    let floor_plan = FloorPlan::parse(text);
-    let evolution = floor_plan.get_stable_evolution();
-
-    let occupied_seats = evolution.get_number_of_occupied_seat();
-
-    println!("Occupied seats: {}", occupied_seats);
-
-    println!("Part 2");
-
-    let evolution = floor_plan.get_stable_evolution_part2();
-
-    let occupied_seats = evolution.get_number_of_occupied_seat();
-
-    println!("Occupied seats: {}", occupied_seats);
+    runner::day_main(11);
 }
@@ -15,22 +15,8 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2020::day13::*;
+use advent_of_code_2020::runner;
 
 fn main() {
-    let text = include_str!("../../data/input-day-13.txt");
-
-    println!("Part 1");
-
-    let schedule = Schedule::parse(text);
-
-    let (time_till_next_bus, available_bus) = schedule.get_earliest_bus();
-
-    println!("Bus id: {}", time_till_next_bus * available_bus);
-
-    println!("Part 2");
-
-    let contest_answer = schedule.get_contest_answer();
-
-    println!("Contest answer: {}", contest_answer);
+    runner::day_main(13);
 }
@@ -15,22 +15,8 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2020::day14::*;
+use advent_of_code_2020::runner;
 
 fn main() {
-    let text = include_str!("../../data/input-day-14.txt");
-
-    println!("Part 1");
-
-    let memory = Memory::parse(text);
-    let sum = memory.get_sum();
-
-    println!("Memory sum: {}", sum);
-
-    println!("Part 2");
-
-    let memory = Memory::parse_part2(text);
-    let sum = memory.get_sum();
-
-    println!("Memory sum: {}", sum);
+    runner::day_main(14);
 }
@@ -0,0 +1,161 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use std::collections::HashMap;
+
+pub fn parse(text: &str) -> Result<Vec<i64>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|_| ParseError::new(ParseErrorKind::InvalidNumber, i + 1, 1, line))
+        })
+        .collect()
+}
+
+fn find_pairs(numbers: &[i64], order: &[usize], target: i64) -> Vec<Vec<usize>> {
+    let mut seen: HashMap<i64, Vec<usize>> = HashMap::new();
+    let mut pairs = vec![];
+    for &index in order {
+        let number = numbers[index];
+        if let Some(matches) = seen.get(&(target - number)) {
+            pairs.extend(matches.iter().map(|&other| vec![other, index]));
+        }
+        seen.entry(number).or_default().push(index);
+    }
+    pairs
+}
+
+fn find_sorted_k_sum(numbers: &[i64], order: &[usize], k: usize, target: i64) -> Vec<Vec<usize>> {
+    if k == 2 {
+        return find_pairs(numbers, order, target);
+    }
+
+    let mut tuples = vec![];
+    for (position, &index) in order.iter().enumerate() {
+        // The order is ascending, so no tuple starting here can be smaller than k times this number
+        if numbers[index] * k as i64 > target {
+            break;
+        }
+        for mut tuple in find_sorted_k_sum(
+            numbers,
+            &order[position + 1..],
+            k - 1,
+            target - numbers[index],
+        ) {
+            tuple.push(index);
+            tuples.push(tuple);
+        }
+    }
+    tuples
+}
+
+pub fn find_k_sum(numbers: &[i64], k: usize, target: i64) -> Vec<Vec<usize>> {
+    let mut tuples = match k {
+        0 => return vec![],
+        1 => numbers
+            .iter()
+            .enumerate()
+            .filter(|(_, &number)| number == target)
+            .map(|(index, _)| vec![index])
+            .collect(),
+        _ => {
+            let mut order: Vec<usize> = (0..numbers.len()).collect();
+            order.sort_by_key(|&index| numbers[index]);
+            find_sorted_k_sum(numbers, &order, k, target)
+        }
+    };
+    tuples.iter_mut().for_each(|tuple| tuple.sort_unstable());
+    tuples.sort();
+    tuples
+}
+
+pub fn find_k_sum_product(numbers: &[i64], k: usize, target: i64) -> Option<i64> {
+    find_k_sum(numbers, k, target)
+        .first()
+        .map(|tuple| tuple.iter().map(|&index| numbers[index]).product())
+}
+
+pub struct Day01;
+
+impl Solver for Day01 {
+    type Input = Vec<i64>;
+
+    fn parse(text: &str) -> Result<Vec<i64>, ParseError> {
+        parse(text)
+    }
+
+    fn part1(numbers: &Vec<i64>) -> String {
+        match find_k_sum_product(numbers, 2, 2020) {
+            Some(product) => product.to_string(),
+            None => String::from("No two entries sum to 2020"),
+        }
+    }
+
+    fn part2(numbers: &Vec<i64>) -> String {
+        match find_k_sum_product(numbers, 3, 2020) {
+            Some(product) => product.to_string(),
+            None => String::from("No three entries sum to 2020"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let text = "\
+1721
+979
+366
+299
+675
+1456";
+
+        let numbers = parse(text).unwrap();
+
+        assert_eq!(find_k_sum(&numbers, 2, 2020), [[0, 3]]);
+        assert_eq!(find_k_sum_product(&numbers, 2, 2020), Some(514579));
+
+        assert_eq!(find_k_sum(&numbers, 3, 2020), [[1, 2, 4]]);
+        assert_eq!(find_k_sum_product(&numbers, 3, 2020), Some(241861950));
+    }
+
+    #[test]
+    fn test_distinct_indices() {
+        let numbers = [1010, 5, 1010, 2015];
+
+        assert_eq!(find_k_sum(&numbers, 2, 2020), [[0, 2], [1, 3]]);
+        assert_eq!(find_k_sum(&[1010], 2, 2020), Vec::<Vec<usize>>::new());
+        assert_eq!(Day01::part1(&vec![1010]), "No two entries sum to 2020");
+        assert_eq!(Day01::part2(&vec![]), "No three entries sum to 2020");
+    }
+
+    #[test]
+    fn test_larger_k() {
+        let numbers = [-3, 1, 2, 4, 6, 0];
+
+        assert_eq!(find_k_sum(&numbers, 1, 4), [[3]]);
+        assert_eq!(find_k_sum(&numbers, 4, 3), [[0, 2, 3, 5]]);
+        assert_eq!(find_k_sum(&numbers, 6, 10), [[0, 1, 2, 3, 4, 5]]);
+        assert_eq!(find_k_sum(&numbers, 7, 10), Vec::<Vec<usize>>::new());
+    }
+}
@@ -0,0 +1,238 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{NomError, ParseError};
+use crate::Solver;
+use nom::character::complete::{char, digit1, not_line_ending, satisfy};
+use nom::combinator::{all_consuming, map_res};
+use nom::IResult;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PasswordEntry {
+    pub min: usize,
+    pub max: usize,
+    pub letter: char,
+    pub password: String,
+}
+
+fn number(i: &str) -> IResult<&str, usize, NomError<'_>> {
+    map_res(digit1, str::parse)(i)
+}
+
+fn parser(i: &str) -> IResult<&str, PasswordEntry, NomError<'_>> {
+    let (i, min) = number(i)?;
+    let (i, _) = char('-')(i)?;
+    let (i, max) = number(i)?;
+    let (i, _) = char(' ')(i)?;
+    let (i, letter) = satisfy(|c| c.is_alphabetic())(i)?;
+    let (i, _) = char(':')(i)?;
+    let (i, _) = char(' ')(i)?;
+    let (i, password) = not_line_ending(i)?;
+
+    Ok((
+        i,
+        PasswordEntry {
+            min,
+            max,
+            letter,
+            password: String::from(password),
+        },
+    ))
+}
+
+impl PasswordEntry {
+    pub fn parse(line: &str) -> Result<PasswordEntry, ParseError> {
+        all_consuming(parser)(line)
+            .map(|(_, entry)| entry)
+            .map_err(|error| ParseError::from_nom(line, error))
+    }
+
+    pub fn parse_list(text: &str) -> Result<Vec<PasswordEntry>, ParseError> {
+        text.lines()
+            .enumerate()
+            .map(|(i, line)| PasswordEntry::parse(line).map_err(|error| error.at_line(i + 1)))
+            .collect()
+    }
+
+    fn has_letter_at(&self, position: usize) -> bool {
+        position
+            .checked_sub(1)
+            .and_then(|index| self.password.chars().nth(index))
+            == Some(self.letter)
+    }
+}
+
+pub trait PasswordPolicy {
+    fn is_valid(&self, entry: &PasswordEntry) -> bool;
+}
+
+/// The letter must occur between `min` and `max` times.
+pub struct CountRangePolicy;
+
+impl PasswordPolicy for CountRangePolicy {
+    fn is_valid(&self, entry: &PasswordEntry) -> bool {
+        let count = entry
+            .password
+            .chars()
+            .filter(|&c| c == entry.letter)
+            .count();
+        (entry.min..=entry.max).contains(&count)
+    }
+}
+
+/// The letter must occur at exactly one of the 1-based positions `min` and `max`.
+pub struct PositionalXorPolicy;
+
+impl PasswordPolicy for PositionalXorPolicy {
+    fn is_valid(&self, entry: &PasswordEntry) -> bool {
+        entry.has_letter_at(entry.min) ^ entry.has_letter_at(entry.max)
+    }
+}
+
+pub fn count_valid(entries: &[PasswordEntry], policy: &dyn PasswordPolicy) -> usize {
+    entries
+        .iter()
+        .filter(|entry| policy.is_valid(entry))
+        .count()
+}
+
+pub struct Day02;
+
+impl Solver for Day02 {
+    type Input = Vec<PasswordEntry>;
+
+    fn parse(text: &str) -> Result<Vec<PasswordEntry>, ParseError> {
+        PasswordEntry::parse_list(text)
+    }
+
+    fn part1(entries: &Vec<PasswordEntry>) -> String {
+        count_valid(entries, &CountRangePolicy).to_string()
+    }
+
+    fn part2(entries: &Vec<PasswordEntry>) -> String {
+        count_valid(entries, &PositionalXorPolicy).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseErrorKind;
+
+    const EXAMPLE: &str = "\
+1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc";
+
+    #[test]
+    fn test_parse() {
+        let entry = PasswordEntry::parse("1-3 a: abcde").unwrap();
+        assert_eq!(
+            entry,
+            PasswordEntry {
+                min: 1,
+                max: 3,
+                letter: 'a',
+                password: String::from("abcde"),
+            }
+        );
+
+        for password in ["abc de", "p4ss-w0rd!", ""].iter() {
+            let entry = PasswordEntry::parse(&format!("1-3 a: {}", password)).unwrap();
+            assert_eq!(entry.password, *password);
+        }
+
+        let entries = PasswordEntry::parse_list(EXAMPLE).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].max, 9);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            PasswordEntry::parse("1-x a: abcde"),
+            Err(ParseError::new(ParseErrorKind::InvalidNumber, 1, 3, "x"))
+        );
+        assert_eq!(
+            PasswordEntry::parse("1+3 a: abcde"),
+            Err(ParseError::new(
+                ParseErrorKind::MissingSeparator('-'),
+                1,
+                2,
+                "+3"
+            ))
+        );
+        assert_eq!(
+            PasswordEntry::parse("1-3 7: abcde"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 1, 5, "7"))
+        );
+        assert_eq!(
+            PasswordEntry::parse_list("1-3 a: abcde\n1-3 b"),
+            Err(ParseError::new(ParseErrorKind::UnexpectedEnd, 2, 6, ""))
+        );
+    }
+
+    #[test]
+    fn test_count_range_policy() {
+        let entries = PasswordEntry::parse_list(EXAMPLE).unwrap();
+        assert!(CountRangePolicy.is_valid(&entries[0]));
+        assert!(!CountRangePolicy.is_valid(&entries[1]));
+        assert!(CountRangePolicy.is_valid(&entries[2]));
+        assert_eq!(count_valid(&entries, &CountRangePolicy), 2);
+
+        let entry = PasswordEntry::parse("1-3 a: a b").unwrap();
+        assert!(CountRangePolicy.is_valid(&entry));
+        let entry = PasswordEntry::parse("1-3 a: ").unwrap();
+        assert!(!CountRangePolicy.is_valid(&entry));
+    }
+
+    #[test]
+    fn test_positional_xor_policy() {
+        let entries = PasswordEntry::parse_list(EXAMPLE).unwrap();
+        assert!(PositionalXorPolicy.is_valid(&entries[0]));
+        assert!(!PositionalXorPolicy.is_valid(&entries[1]));
+        assert!(!PositionalXorPolicy.is_valid(&entries[2]));
+        assert_eq!(count_valid(&entries, &PositionalXorPolicy), 1);
+
+        let entry = PasswordEntry::parse("0-30 a: abcde").unwrap();
+        assert!(!PositionalXorPolicy.is_valid(&entry));
+
+        let entry = PasswordEntry::parse("1-3 a: a b").unwrap();
+        assert!(PositionalXorPolicy.is_valid(&entry));
+        let entry = PasswordEntry::parse("2-3 a: a a").unwrap();
+        assert!(PositionalXorPolicy.is_valid(&entry));
+        let entry = PasswordEntry::parse("1-3 a: ").unwrap();
+        assert!(!PositionalXorPolicy.is_valid(&entry));
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        struct ForbiddenSubstringPolicy(&'static str);
+
+        impl PasswordPolicy for ForbiddenSubstringPolicy {
+            fn is_valid(&self, entry: &PasswordEntry) -> bool {
+                !entry.password.contains(self.0)
+            }
+        }
+
+        let entries = PasswordEntry::parse_list(EXAMPLE).unwrap();
+        assert_eq!(count_valid(&entries, &ForbiddenSubstringPolicy("cd")), 1);
+
+        let entry = PasswordEntry::parse("1-3 a: a b").unwrap();
+        assert!(!ForbiddenSubstringPolicy(" ").is_valid(&entry));
+    }
+}
@@ -15,7 +15,10 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::cmp::max;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::grid::Grid;
+use crate::Solver;
+use std::collections::BTreeMap;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Point {
@@ -23,75 +26,221 @@ pub struct Point {
     pub y: usize,
 }
 
+#[derive(Clone)]
 pub struct Map {
-    trees: Vec<Point>,
-    size: Point,
+    trees: Grid<bool>,
 }
 
 impl Map {
-    pub fn from_text<'a, T: Into<&'a str>>(input: T) -> Map {
-        let input = input.into().lines();
+    pub fn from_text<'a, T: Into<&'a str>>(input: T) -> Result<Map, ParseError> {
+        let trees = Grid::parse(input.into(), |char| match char {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        // Columns wrap modulo the width, so rows must not be empty
+        if trees.width() == 0 && trees.height() > 0 {
+            return Err(ParseError::new(ParseErrorKind::InvalidLength, 1, 1, ""));
+        }
+        Ok(Map { trees })
+    }
 
-        let mut map = Map {
-            trees: vec![],
-            size: Point { x: 0, y: 0 },
-        };
+    pub fn size(&self) -> Point {
+        Point {
+            x: self.trees.height(),
+            y: self.trees.width(),
+        }
+    }
 
-        for (x, line) in input.enumerate() {
-            map.size.x = max(x + 1, map.size.x);
-            for (y, char) in line.chars().enumerate() {
-                map.size.y = max(y + 1, map.size.y);
-                if char == '#' {
-                    map.trees.push(Point { x, y });
-                }
+    /// Columns wrap around, as the map repeats to the right.
+    pub fn has_tree_at(&self, p: Point) -> bool {
+        let width = self.trees.width();
+        width > 0 && self.trees.get((p.y % width, p.x)) == Some(&true)
+    }
+
+    /// Counts the trees hit when moving `direction.x` rows down and
+    /// `direction.y` columns right on every step.
+    pub fn traverse(&self, direction: Point) -> usize {
+        assert!(direction.x > 0, "slope must move down");
+        let size = self.size();
+        let mut count = 0;
+        let mut y = 0;
+        for x in (0..size.x).step_by(direction.x) {
+            if self.has_tree_at(Point { x, y }) {
+                count += 1
             }
+            y = (y + direction.y % size.y) % size.y;
         }
+        count
+    }
 
-        map
+    pub fn traverse_all(&self, directions: &[Point]) -> (Vec<usize>, usize) {
+        let counts: Vec<usize> = directions
+            .iter()
+            .map(|&direction| self.traverse(direction))
+            .collect();
+        let product = counts.iter().product();
+        (counts, product)
     }
 
-    pub fn extend_point(&self, tree: &Point) -> Point {
-        Point {
-            x: tree.x,
-            y: tree.y + self.size.y,
-        }
+    /// Traverses every slope moving at most `max_right` columns right and
+    /// between 1 and `max_down` rows down per step, ranked from fewest to
+    /// most trees. Equivalent slopes such as (2, 6) and (1, 3) are only
+    /// included once, in their reduced form.
+    pub fn search_slopes(&self, max_right: usize, max_down: usize) -> Vec<(Point, usize)> {
+        let mut slopes: Vec<(Point, usize)> = (1..=max_down)
+            .flat_map(|x| (0..=max_right).map(move |y| Point { x, y }))
+            .filter(|direction| gcd(direction.x, direction.y) == 1)
+            .map(|direction| (direction, self.traverse(direction)))
+            .collect();
+        slopes.sort_by_key(|&(direction, count)| (count, direction));
+        slopes
     }
+}
 
-    pub fn extend(mut self) -> Map {
-        let mut new_trees = vec![];
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Marker {
+    pub open: char,
+    pub tree: char,
+    pub colour: u8,
+}
 
-        for tree in self.trees.iter() {
-            new_trees.push(self.extend_point(tree));
-            new_trees.push(*tree);
-        }
+/// The slopes checked in part 2
+pub const SLOPES: [Point; 5] = [
+    Point { x: 1, y: 1 },
+    Point { x: 1, y: 3 },
+    Point { x: 1, y: 5 },
+    Point { x: 1, y: 7 },
+    Point { x: 2, y: 1 },
+];
+
+/// Rendered maps are cut off after this many columns, rounded down to whole
+/// copies of the map.
+pub const MAX_RENDER_WIDTH: usize = 1000;
+
+/// Markers used for the routes in `Map::render`, in order of the given slopes.
+/// The first one matches the puzzle illustration.
+pub const MARKERS: [Marker; 5] = [
+    Marker {
+        open: 'O',
+        tree: 'X',
+        colour: 31,
+    },
+    Marker {
+        open: 'o',
+        tree: 'x',
+        colour: 32,
+    },
+    Marker {
+        open: 'V',
+        tree: 'W',
+        colour: 33,
+    },
+    Marker {
+        open: 'v',
+        tree: 'w',
+        colour: 34,
+    },
+    Marker {
+        open: 'U',
+        tree: 'Y',
+        colour: 35,
+    },
+];
 
-        self.trees = new_trees;
-        self.size = self.extend_point(&self.size);
-        self
+impl Map {
+    /// The squares visited by a route, up to the first column that no
+    /// longer fits in a `usize`.
+    fn visited(&self, direction: Point) -> Vec<Point> {
+        assert!(direction.x > 0, "slope must move down");
+        (0..self.size().x)
+            .step_by(direction.x)
+            .enumerate()
+            .map_while(|(step, x)| {
+                Some(Point {
+                    x,
+                    y: step.checked_mul(direction.y)?,
+                })
+            })
+            .collect()
     }
 
-    pub fn auto_extend(self, direction: Point) -> Map {
-        let mut map = self;
-        while map.size.y < map.size.x * direction.y {
-            map = map.extend();
-        }
-        map
+    /// Draws the map, repeated to the right as far as the routes go but at
+    /// most `MAX_RENDER_WIDTH` columns, with the squares visited by each slope
+    /// marked. Where routes cross, the earlier slope is drawn.
+    pub fn render(&self, directions: &[Point]) -> String {
+        self.render_with(directions, false)
     }
 
-    pub fn has_tree_at(&self, p: Point) -> bool {
-        self.trees.iter().any(|tree| *tree == p)
+    /// Like `render`, with the route markers coloured using ANSI escape codes.
+    pub fn render_ansi(&self, directions: &[Point]) -> String {
+        self.render_with(directions, true)
     }
 
-    pub fn traverse(&self, direction: Point) -> usize {
-        let mut count = 0;
-        for x in (0..self.size.x).step_by(direction.x) {
-            let y = x * direction.y / direction.x;
-            assert!(y < self.size.y);
-            if self.has_tree_at(Point { x, y }) {
-                count += 1
+    fn render_with(&self, directions: &[Point], ansi: bool) -> String {
+        let size = self.size();
+        let mut markers = BTreeMap::new();
+        for (index, &direction) in directions.iter().enumerate() {
+            for point in self.visited(direction) {
+                markers
+                    .entry(point)
+                    .or_insert(MARKERS[index % MARKERS.len()]);
             }
         }
-        count
+
+        let tile_width = size.y.max(1);
+        let last_column = markers.keys().map(|point| point.y).max().unwrap_or(0);
+        let max_width = (MAX_RENDER_WIDTH / tile_width).max(1) * tile_width;
+        let width = (last_column / tile_width + 1)
+            .saturating_mul(tile_width)
+            .min(max_width);
+
+        let mut text = String::new();
+        for x in 0..size.x {
+            for y in 0..width {
+                let tree = self.has_tree_at(Point { x, y });
+                match markers.get(&Point { x, y }) {
+                    Some(marker) => {
+                        let char = if tree { marker.tree } else { marker.open };
+                        if ansi {
+                            text.push_str(&format!("\x1b[{}m{}\x1b[0m", marker.colour, char));
+                        } else {
+                            text.push(char);
+                        }
+                    }
+                    None => text.push(if tree { '#' } else { '.' }),
+                }
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub struct Day03;
+
+impl Solver for Day03 {
+    type Input = Map;
+
+    fn parse(text: &str) -> Result<Map, ParseError> {
+        Map::from_text(text)
+    }
+
+    fn part1(map: &Map) -> String {
+        map.traverse(Point { x: 1, y: 3 }).to_string()
+    }
+
+    fn part2(map: &Map) -> String {
+        let (_, product) = map.traverse_all(&SLOPES);
+        product.to_string()
     }
 }
 
@@ -106,11 +255,17 @@ mod tests {
 #..
 .#.";
 
-        let map = Map::from_text(text);
+        let map = Map::from_text(text).unwrap();
 
-        assert_eq!(map.size, Point { x: 3, y: 3 });
+        assert_eq!(map.size(), Point { x: 3, y: 3 });
+        let trees: Vec<Point> = map
+            .trees
+            .iter()
+            .filter(|(_, &tree)| tree)
+            .map(|((y, x), _)| Point { x, y })
+            .collect();
         assert_eq!(
-            map.trees,
+            trees,
             vec![
                 Point { x: 0, y: 2 },
                 Point { x: 1, y: 0 },
@@ -120,41 +275,36 @@ mod tests {
     }
 
     #[test]
-    fn extend() {
+    fn wrapping() {
         let text = "\
 ..#
 #..
 .#.";
 
-        let map = Map::from_text(text);
-        let map = map.extend();
+        let map = Map::from_text(text).unwrap();
 
         let expected_text = "\
 ..#..#
 #..#..
 .#..#.";
-        let expected_map = Map::from_text(expected_text);
+        let expected_map = Map::from_text(expected_text).unwrap();
 
-        assert_eq!(map.size, expected_map.size);
-        assert_eq!(
-            {
-                let mut list = map.trees.clone();
-                list.sort();
-                list
-            },
-            {
-                let mut list = expected_map.trees.clone();
-                list.sort();
-                list
+        for x in 0..3 {
+            for y in 0..6 {
+                assert_eq!(
+                    map.has_tree_at(Point { x, y }),
+                    expected_map.has_tree_at(Point { x, y })
+                );
             }
-        );
+        }
+        assert!(map.has_tree_at(Point { x: 1, y: 3_000_000 }));
+        assert!(!map.has_tree_at(Point { x: 3, y: 0 }));
     }
 
     #[test]
     fn traverse() {
         let text = include_str!("../../data/test-day-03.txt");
-        let map = Map::from_text(text);
-        let map = map.extend().extend().extend();
+        let map = Map::from_text(text).unwrap();
 
         assert_eq!(map.traverse(Point { x: 1, y: 1 }), 2);
         assert_eq!(map.traverse(Point { x: 1, y: 3 }), 7);
@@ -162,4 +312,152 @@ mod tests {
         assert_eq!(map.traverse(Point { x: 1, y: 7 }), 4);
         assert_eq!(map.traverse(Point { x: 2, y: 1 }), 2);
     }
+
+    #[test]
+    fn traverse_steep() {
+        let text = include_str!("../../data/test-day-03.txt");
+        let map = Map::from_text(text).unwrap();
+        let width = map.size().y;
+
+        assert_eq!(
+            map.traverse(Point {
+                x: 1,
+                y: 3 + 1_000_000 * width
+            }),
+            7
+        );
+        assert_eq!(
+            map.traverse(Point {
+                x: 1,
+                y: usize::MAX
+            }),
+            map.traverse(Point {
+                x: 1,
+                y: usize::MAX % width
+            })
+        );
+    }
+
+    #[test]
+    fn traverse_all() {
+        let text = include_str!("../../data/test-day-03.txt");
+        let map = Map::from_text(text).unwrap();
+
+        let (counts, product) = map.traverse_all(&[
+            Point { x: 1, y: 1 },
+            Point { x: 1, y: 3 },
+            Point { x: 1, y: 5 },
+            Point { x: 1, y: 7 },
+            Point { x: 2, y: 1 },
+        ]);
+        assert_eq!(counts, [2, 7, 3, 4, 2]);
+        assert_eq!(product, 336);
+    }
+
+    #[test]
+    fn search_slopes() {
+        let text = include_str!("../../data/test-day-03.txt");
+        let map = Map::from_text(text).unwrap();
+
+        let slopes = map.search_slopes(7, 2);
+        assert_eq!(slopes.len(), 12);
+        assert!(slopes.contains(&(Point { x: 1, y: 3 }, 7)));
+        assert!(slopes.contains(&(Point { x: 2, y: 1 }, 2)));
+        assert!(!slopes
+            .iter()
+            .any(|(direction, _)| *direction == Point { x: 2, y: 2 }));
+        assert!(slopes.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(slopes.last(), Some(&(Point { x: 1, y: 3 }, 7)));
+
+        let slopes = map.search_slopes(6, 6);
+        assert!(slopes.contains(&(Point { x: 1, y: 3 }, 7)));
+        assert!(!slopes
+            .iter()
+            .any(|(direction, _)| *direction == Point { x: 2, y: 6 }));
+        assert_eq!(
+            slopes
+                .iter()
+                .filter(|(direction, _)| direction.y == 0)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn render() {
+        let text = include_str!("../../data/test-day-03.txt");
+        let map = Map::from_text(text).unwrap();
+
+        let expected = "\
+O.##.........##.........##.......
+#..O#...#..#...#...#..#...#...#..
+.#....X..#..#....#..#..#....#..#.
+..#.#...#O#..#.#...#.#..#.#...#.#
+.#...##..#..X...##..#..#...##..#.
+..#.##.......#.X#.......#.##.....
+.#.#.#....#.#.#.#.O..#.#.#.#....#
+.#........#.#........X.#........#
+#.##...#...#.##...#...#.X#...#...
+#...##....##...##....##...#X....#
+.#..#...#.#.#..#...#.#.#..#...X.#
+";
+        assert_eq!(map.render(&[Point { x: 1, y: 3 }]), expected);
+
+        let rendered = map.render(&[Point { x: 1, y: 1 }, Point { x: 2, y: 1 }]);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "O.##.......");
+        assert_eq!(lines[1], "#O..#...#..");
+        assert_eq!(lines[2], ".xO...#..#.");
+        assert_eq!(lines[4], ".#o.O##..#.");
+        assert_eq!(lines[10], ".#..#o..#.X");
+
+        let rendered = map.render_ansi(&[Point { x: 1, y: 3 }]);
+        assert!(rendered.starts_with("\x1b[31mO\x1b[0m.##"));
+        assert!(rendered.contains(".#....\x1b[31mX\x1b[0m..#"));
+    }
+
+    #[test]
+    fn render_steep() {
+        let text = include_str!("../../data/test-day-03.txt");
+        let map = Map::from_text(text).unwrap();
+        let width = map.size().y;
+
+        let steep = Point {
+            x: 1,
+            y: usize::MAX / 4,
+        };
+        assert_eq!(map.visited(steep).len(), 5);
+
+        let rendered = map.render(&[steep]);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0].len(), MAX_RENDER_WIDTH / width * width);
+        assert!(lines[0].starts_with('O'));
+
+        let rendered = map.render(&[Point { x: 1, y: 1 }]);
+        assert_eq!(rendered.lines().next().unwrap().len(), width);
+    }
+
+    #[test]
+    fn test_load_invalid() {
+        let text = "\
+..#
+#.O";
+
+        let error = Map::from_text(text).err().unwrap();
+        assert_eq!(
+            error,
+            ParseError::new(ParseErrorKind::InvalidCharacter, 2, 3, "O")
+        );
+
+        assert_eq!(
+            Map::from_text("\n\n").err(),
+            Some(ParseError::new(ParseErrorKind::InvalidLength, 1, 1, ""))
+        );
+        assert_eq!(
+            Map::from_text("").unwrap().traverse(Point { x: 1, y: 3 }),
+            0
+        );
+    }
 }
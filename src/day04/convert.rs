@@ -0,0 +1,329 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::{Field, Passport};
+use crate::error::{ParseError, ParseErrorKind};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Formats passports in the puzzle's batch format, one line per passport.
+pub fn to_batch_text(passports: &[Passport]) -> String {
+    passports
+        .iter()
+        .map(|passport| {
+            let fields = Field::ALL
+                .iter()
+                .filter_map(|&field| passport.get(field).map(|value| (field.key(), value)))
+                .chain(
+                    passport
+                        .extra
+                        .iter()
+                        .map(|(key, value)| (key.as_str(), value.as_str())),
+                );
+            fields
+                .map(|(key, value)| format!("{}:{}", key, value))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Writes one JSON object per line; missing fields are `null` and unknown
+/// keys are included as extra members.
+pub fn to_json_lines(passports: &[Passport]) -> String {
+    passports
+        .iter()
+        .map(|passport| serde_json::to_string(passport).unwrap() + "\n")
+        .collect()
+}
+
+pub fn from_json_lines(text: &str) -> Result<Vec<Passport>, ParseError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_index, line)| {
+            serde_json::from_str(line).map_err(|error| {
+                let column = error.column().max(1).min(line.len() + 1);
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter,
+                    line_index + 1,
+                    column,
+                    line.get(column - 1..).unwrap_or(""),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Writes a CSV table with a column for every field, followed by a column for
+/// every unknown key in the batch. Missing fields are empty cells.
+pub fn to_csv(passports: &[Passport]) -> String {
+    let extra_keys: BTreeSet<&str> = passports
+        .iter()
+        .flat_map(|passport| passport.extra.keys().map(String::as_str))
+        .collect();
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    let header = Field::ALL
+        .iter()
+        .map(|field| field.key())
+        .chain(extra_keys.iter().copied());
+    writer.write_record(header).unwrap();
+    for passport in passports {
+        let record = Field::ALL
+            .iter()
+            .map(|&field| passport.get(field).unwrap_or(""))
+            .chain(
+                extra_keys
+                    .iter()
+                    .map(|key| passport.extra.get(*key).map_or("", String::as_str)),
+            );
+        writer.write_record(record).unwrap();
+    }
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+/// Reads a CSV table as written by `to_csv`. Empty cells are missing fields,
+/// which is unambiguous because the other formats reject empty values.
+pub fn from_csv(text: &str) -> Result<Vec<Passport>, ParseError> {
+    let csv_error = |error: csv::Error| {
+        let line = error
+            .position()
+            .map_or(1, |position| position.line() as usize);
+        let kind = match error.kind() {
+            csv::ErrorKind::UnequalLengths { .. } => ParseErrorKind::InvalidLength,
+            _ => ParseErrorKind::InvalidCharacter,
+        };
+        ParseError::new(kind, line, 1, text.lines().nth(line - 1).unwrap_or(""))
+    };
+
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let header = reader.headers().map_err(csv_error)?.clone();
+    let mut keys = BTreeSet::new();
+    let mut column = 1;
+    for key in header.iter() {
+        if !keys.insert(key) {
+            return Err(ParseError::new(
+                ParseErrorKind::DuplicateKey,
+                1,
+                column,
+                key,
+            ));
+        }
+        column += key.chars().count() + 1;
+    }
+
+    let mut passports = vec![];
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let mut passport = Passport::default();
+        for (key, value) in header.iter().zip(record.iter()) {
+            if value.is_empty() {
+                continue;
+            }
+            match Field::from_key(key) {
+                Some(field) => *passport.get_mut(field) = Some(String::from(value)),
+                None => {
+                    passport
+                        .extra
+                        .insert(String::from(key), String::from(value));
+                }
+            }
+        }
+        passports.push(passport);
+    }
+    Ok(passports)
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Batch,
+    JsonLines,
+    Csv,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "batch" | "text" => Some(Format::Batch),
+            "json" | "jsonl" => Some(Format::JsonLines),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+
+    pub fn read(self, text: &str) -> Result<Vec<Passport>, ParseError> {
+        match self {
+            Format::Batch => Passport::parse_text(text),
+            Format::JsonLines => from_json_lines(text),
+            Format::Csv => from_csv(text),
+        }
+    }
+
+    pub fn write(self, passports: &[Passport]) -> String {
+        match self {
+            Format::Batch => to_batch_text(passports) + "\n",
+            Format::JsonLines => to_json_lines(passports),
+            Format::Csv => to_csv(passports),
+        }
+    }
+}
+
+// Serialised as a flat object keyed by the three letter field keys.
+impl serde::Serialize for Passport {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(Field::ALL.len() + self.extra.len()))?;
+        for &field in Field::ALL.iter() {
+            map.serialize_entry(field.key(), &self.get(field))?;
+        }
+        for (key, value) in &self.extra {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Passport {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = BTreeMap::<String, Option<String>>::deserialize(deserializer)?;
+        let mut passport = Passport::default();
+        for (key, value) in fields {
+            let value = match value {
+                Some(value) if value.is_empty() => {
+                    return Err(serde::de::Error::invalid_length(0, &"a non-empty value"))
+                }
+                Some(value) => value,
+                None => continue,
+            };
+            match Field::from_key(&key) {
+                Some(field) => *passport.get_mut(field) = Some(value),
+                None => {
+                    passport.extra.insert(key, value);
+                }
+            }
+        }
+        Ok(passport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648 scanner:\"B,7\"
+iyr:2011 ecl:brn hgt:59in";
+
+    #[test]
+    fn test_json_lines() {
+        let passports = Passport::parse_text(EXAMPLE).unwrap();
+        let json = to_json_lines(&passports);
+
+        assert_eq!(
+            json.lines().nth(1).unwrap(),
+            "{\"byr\":\"1929\",\"iyr\":\"2013\",\"eyr\":\"2023\",\"hgt\":null,\
+             \"hcl\":\"#cfa07d\",\"ecl\":\"amb\",\"pid\":\"028048884\",\"cid\":\"350\"}"
+        );
+        assert!(json
+            .lines()
+            .nth(3)
+            .unwrap()
+            .ends_with("\"cid\":null,\"scanner\":\"\\\"B,7\\\"\"}"));
+        assert_eq!(from_json_lines(&json).unwrap(), passports);
+
+        assert_eq!(
+            from_json_lines("{\"cid\":\"\"}"),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidCharacter,
+                1,
+                1,
+                "{\"cid\":\"\"}"
+            ))
+        );
+        assert_eq!(
+            from_json_lines("{\"byr\":\"1929\"}\n{\"byr\":19}"),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidCharacter,
+                2,
+                9,
+                "9}"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let passports = Passport::parse_text(EXAMPLE).unwrap();
+        let csv = to_csv(&passports);
+
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("byr,iyr,eyr,hgt,hcl,ecl,pid,cid,scanner")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("1937,2017,2020,183cm,#fffffd,gry,860033327,147,")
+        );
+        assert_eq!(
+            lines.nth(2),
+            Some(",2011,2025,59in,#cfa07d,brn,166559648,,\"\"\"B,7\"\"\"")
+        );
+        assert_eq!(from_csv(&csv).unwrap(), passports);
+
+        assert_eq!(
+            from_csv("byr,iyr\n1937,2017\n1929"),
+            Err(ParseError::new(ParseErrorKind::InvalidLength, 3, 1, "1929"))
+        );
+        assert_eq!(
+            from_csv("byr,iyr,byr\n1937,2017,1929"),
+            Err(ParseError::new(ParseErrorKind::DuplicateKey, 1, 9, "byr"))
+        );
+        assert_eq!(
+            from_csv("byr,cid\n1937,\"\"").unwrap()[0].get(Field::CountryId),
+            None
+        );
+    }
+
+    #[test]
+    fn test_batch_text() {
+        let passports = Passport::parse_text(EXAMPLE).unwrap();
+        let text = to_batch_text(&passports);
+
+        assert!(text.starts_with(
+            "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327 cid:147\n\n"
+        ));
+        assert_eq!(Passport::parse_text(&text).unwrap(), passports);
+
+        for format in [Format::Batch, Format::JsonLines, Format::Csv].iter() {
+            let written = format.write(&passports);
+            assert_eq!(format.read(&written).unwrap(), passports, "{:?}", format);
+        }
+    }
+}
@@ -15,8 +15,110 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::error::{column_of, NomError, ParseError, ParseErrorKind};
+use crate::Solver;
+use nom::bytes::complete::take_while1;
+use nom::character::complete::{char, multispace0, multispace1};
+use nom::combinator::all_consuming;
+use nom::multi::separated_list0;
+use nom::sequence::delimited;
+use nom::IResult;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 use std::string::String;
 
+pub mod convert;
+mod schema;
+mod validated;
+
+pub use schema::{Failure, FieldError, Report, Rule, Schema};
+pub use validated::{EyeColor, Height, HeightUnit, PassportId, Rgb, ValidatedPassport};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Field {
+    BirthYear,
+    IssueYear,
+    ExpirationYear,
+    Height,
+    HairColor,
+    EyeColor,
+    PassportId,
+    CountryId,
+}
+
+impl Field {
+    pub const ALL: [Field; 8] = [
+        Field::BirthYear,
+        Field::IssueYear,
+        Field::ExpirationYear,
+        Field::Height,
+        Field::HairColor,
+        Field::EyeColor,
+        Field::PassportId,
+        Field::CountryId,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Field::BirthYear => "byr",
+            Field::IssueYear => "iyr",
+            Field::ExpirationYear => "eyr",
+            Field::Height => "hgt",
+            Field::HairColor => "hcl",
+            Field::EyeColor => "ecl",
+            Field::PassportId => "pid",
+            Field::CountryId => "cid",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Field> {
+        Field::ALL.iter().copied().find(|field| field.key() == key)
+    }
+}
+
+fn field(i: &str) -> IResult<&str, (&str, &str), NomError<'_>> {
+    let (rest, key) = take_while1(|c: char| c != ':' && !c.is_whitespace())(i)?;
+    let (rest, _) = char(':')(rest).map_err(|_: nom::Err<NomError>| {
+        nom::Err::Failure(NomError::new(i, ParseErrorKind::MissingSeparator(':')))
+    })?;
+    let (rest, value) =
+        take_while1(|c: char| !c.is_whitespace())(rest).map_err(|_: nom::Err<NomError>| {
+            nom::Err::Failure(NomError::new(i, ParseErrorKind::InvalidLength))
+        })?;
+    Ok((rest, (key, value)))
+}
+
+fn fields(i: &str) -> IResult<&str, Vec<(&str, &str)>, NomError<'_>> {
+    delimited(
+        multispace0,
+        separated_list0(multispace1, field),
+        multispace0,
+    )(i)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Warning {
+    pub line: usize,
+    pub column: usize,
+    pub key: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: duplicate key '{}'",
+            self.line, self.column, self.key
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Batch {
+    pub passports: Vec<Passport>,
+    pub warnings: Vec<Warning>,
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Passport {
     pub birth_year: Option<String>,
@@ -27,120 +129,144 @@ pub struct Passport {
     pub eye_color: Option<String>,
     pub passport_id: Option<String>,
     pub country_id: Option<String>,
+    pub extra: BTreeMap<String, String>,
 }
 
 impl Passport {
-    pub fn parse_text(text: &str) -> Vec<Passport> {
-        let mut passport_list = vec![];
+    /// Parses a batch of passports separated by blank lines, where fields are
+    /// separated by any whitespace. Keys that are not passport fields end up in
+    /// `extra`; a key repeated within one passport keeps its last value and is
+    /// reported as a warning.
+    pub fn parse_batch(text: &str) -> Result<Batch, ParseError> {
+        let mut batch = Batch::default();
         let mut passport = Passport::default();
-        for line in text.lines() {
-            if line.is_empty() {
-                passport_list.push(passport.clone());
+        let mut seen_keys = BTreeSet::new();
+        for (line_index, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            let (_, fields) = all_consuming(fields)(line)
+                .map_err(|error| ParseError::from_nom(line, error).at_line(line_index + 1))?;
+            if fields.is_empty() {
+                if passport != Passport::default() {
+                    batch.passports.push(passport);
+                }
                 passport = Passport::default();
-            } else {
-                for field in line.split(' ') {
-                    let mut split_field = field.split(':');
-                    let key = split_field.next().unwrap();
-                    let value = split_field.next().unwrap();
-                    match key {
-                        "byr" => {
-                            passport.birth_year = Some(String::from(value));
-                        }
-                        "iyr" => {
-                            passport.issue_year = Some(String::from(value));
-                        }
-                        "eyr" => {
-                            passport.expiration_year = Some(String::from(value));
-                        }
-                        "hgt" => {
-                            passport.height = Some(String::from(value));
-                        }
-                        "hcl" => {
-                            passport.hair_color = Some(String::from(value));
-                        }
-                        "ecl" => {
-                            passport.eye_color = Some(String::from(value));
-                        }
-                        "pid" => {
-                            passport.passport_id = Some(String::from(value));
-                        }
-                        "cid" => {
-                            passport.country_id = Some(String::from(value));
-                        }
-                        _ => panic!("Invalid field key"),
+                seen_keys.clear();
+                continue;
+            }
+            for (key, value) in fields {
+                if !seen_keys.insert(key) {
+                    batch.warnings.push(Warning {
+                        line: line_index + 1,
+                        column: column_of(line, key),
+                        key: String::from(key),
+                    });
+                }
+                match Field::from_key(key) {
+                    Some(field) => *passport.get_mut(field) = Some(String::from(value)),
+                    None => {
+                        passport
+                            .extra
+                            .insert(String::from(key), String::from(value));
                     }
                 }
             }
         }
-        passport_list.push(passport);
-        passport_list
+        if passport != Passport::default() {
+            batch.passports.push(passport);
+        }
+        Ok(batch)
+    }
+
+    pub fn parse_text(text: &str) -> Result<Vec<Passport>, ParseError> {
+        Ok(Passport::parse_batch(text)?.passports)
+    }
+
+    fn get_mut(&mut self, field: Field) -> &mut Option<String> {
+        match field {
+            Field::BirthYear => &mut self.birth_year,
+            Field::IssueYear => &mut self.issue_year,
+            Field::ExpirationYear => &mut self.expiration_year,
+            Field::Height => &mut self.height,
+            Field::HairColor => &mut self.hair_color,
+            Field::EyeColor => &mut self.eye_color,
+            Field::PassportId => &mut self.passport_id,
+            Field::CountryId => &mut self.country_id,
+        }
+    }
+
+    pub fn get(&self, field: Field) -> Option<&str> {
+        match field {
+            Field::BirthYear => &self.birth_year,
+            Field::IssueYear => &self.issue_year,
+            Field::ExpirationYear => &self.expiration_year,
+            Field::Height => &self.height,
+            Field::HairColor => &self.hair_color,
+            Field::EyeColor => &self.eye_color,
+            Field::PassportId => &self.passport_id,
+            Field::CountryId => &self.country_id,
+        }
+        .as_deref()
     }
 
     pub fn fields_valid(&self) -> bool {
-        self.passport_id.is_some()
-            && self.birth_year.is_some()
-            && self.issue_year.is_some()
-            && self.expiration_year.is_some()
-            && self.height.is_some()
-            && self.hair_color.is_some()
-            && self.eye_color.is_some()
+        Schema::part1().validate(self).is_valid()
     }
 
     pub fn data_valid(&self) -> bool {
-        self.fields_valid()
-            && self.passport_id_valid()
-            && self.birth_year_valid()
-            && self.issue_year_valid()
-            && self.expiration_year_valid()
-            && self.height_valid()
-            && self.hair_color_valid()
-            && self.eye_color_valid()
+        Schema::part2().validate(self).is_valid()
+    }
+
+    fn field_valid(&self, field: Field) -> bool {
+        Schema::part2().check_field(self, field).is_none()
     }
 
     pub fn birth_year_valid(&self) -> bool {
-        let birth_year: u32 = self.birth_year.as_ref().unwrap().parse().unwrap();
-        birth_year >= 1920 && birth_year <= 2002
+        self.field_valid(Field::BirthYear)
     }
 
     pub fn issue_year_valid(&self) -> bool {
-        let issue_year: u32 = self.issue_year.as_ref().unwrap().parse().unwrap();
-        issue_year >= 2010 && issue_year <= 2020
+        self.field_valid(Field::IssueYear)
     }
     pub fn expiration_year_valid(&self) -> bool {
-        let expiration_year: u32 = self.expiration_year.as_ref().unwrap().parse().unwrap();
-        expiration_year >= 2020 && expiration_year <= 2030
+        self.field_valid(Field::ExpirationYear)
     }
     pub fn height_valid(&self) -> bool {
-        let height = self.height.as_ref().unwrap();
-        if height.ends_with("in") {
-            let height: u32 = height[..2].parse().unwrap_or_default();
-            height >= 59 && height <= 76
-        } else if height.ends_with("cm") {
-            let height: u32 = height[..3].parse().unwrap_or_default();
-            height >= 150 && height <= 193
-        } else {
-            false
-        }
+        self.field_valid(Field::Height)
     }
     pub fn hair_color_valid(&self) -> bool {
-        let hair_color = self.hair_color.as_ref().unwrap();
-        hair_color.starts_with('#') && i64::from_str_radix(&hair_color[1..], 16).is_ok()
+        self.field_valid(Field::HairColor)
     }
     pub fn eye_color_valid(&self) -> bool {
-        match self.eye_color.as_ref().unwrap().as_str() {
-            "amb" => true,
-            "blu" => true,
-            "brn" => true,
-            "gry" => true,
-            "grn" => true,
-            "hzl" => true,
-            "oth" => true,
-            _ => false,
-        }
+        self.field_valid(Field::EyeColor)
     }
     pub fn passport_id_valid(&self) -> bool {
-        let passport_id = self.passport_id.as_ref().unwrap();
-        passport_id.chars().all(|c| c.is_numeric()) && passport_id.len() == 9
+        self.field_valid(Field::PassportId)
+    }
+}
+
+pub struct Day04;
+
+impl Solver for Day04 {
+    type Input = Vec<Passport>;
+
+    fn parse(text: &str) -> Result<Vec<Passport>, ParseError> {
+        Passport::parse_text(text)
+    }
+
+    fn part1(passport_list: &Vec<Passport>) -> String {
+        passport_list
+            .iter()
+            .filter(|passport| passport.fields_valid())
+            .count()
+            .to_string()
+    }
+
+    fn part2(passport_list: &Vec<Passport>) -> String {
+        passport_list
+            .iter()
+            .filter(|passport| passport.data_valid())
+            .count()
+            .to_string()
     }
 }
 
@@ -155,7 +281,7 @@ ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
 byr:1937 iyr:2017 cid:147 hgt:183cm
 ";
 
-        let passport_list = Passport::parse_text(text);
+        let passport_list = Passport::parse_text(text).unwrap();
         let passport = passport_list.first().unwrap().clone();
 
         assert_eq!(passport.country_id.unwrap(), "147");
@@ -168,6 +294,70 @@ byr:1937 iyr:2017 cid:147 hgt:183cm
         assert_eq!(passport.birth_year.unwrap(), "1937");
     }
 
+    #[test]
+    fn test_parse_invalid() {
+        let text = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 xyz:147 hgt:183cm
+";
+
+        let passport_list = Passport::parse_text(text).unwrap();
+        assert_eq!(passport_list[0].extra["xyz"], "147");
+        assert_eq!(passport_list[0].country_id, None);
+
+        let error = Passport::parse_text("ecl:gry pid").err().unwrap();
+        assert_eq!(
+            error,
+            ParseError::new(ParseErrorKind::MissingSeparator(':'), 1, 9, "pid")
+        );
+
+        let error = Passport::parse_text("ecl:gry\ncid: pid:1").err().unwrap();
+        assert_eq!(
+            error,
+            ParseError::new(ParseErrorKind::InvalidLength, 2, 1, "cid")
+        );
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let text = "\r\n\
+ecl:gry\tpid:860033327  eyr:2020 hcl:#fffffd\r\n\
+byr:1937 iyr:2017 cid:147 hgt:183cm\r\n\
+\r\n\
+\t\r\n\
+\r\n\
+iyr:2013 ecl:amb scanner:B7 eyr:2023 pid:028048884\r\n\
+hcl:#cfa07d byr:1929 ecl:brn\r\n\
+\r\n";
+
+        let batch = Passport::parse_batch(text).unwrap();
+        assert_eq!(batch.passports.len(), 2);
+
+        let passport = &batch.passports[0];
+        assert_eq!(passport.eye_color.as_deref(), Some("gry"));
+        assert_eq!(passport.passport_id.as_deref(), Some("860033327"));
+        assert_eq!(passport.height.as_deref(), Some("183cm"));
+        assert!(passport.extra.is_empty());
+        assert!(passport.fields_valid());
+
+        let passport = &batch.passports[1];
+        assert_eq!(passport.eye_color.as_deref(), Some("brn"));
+        assert_eq!(passport.extra["scanner"], "B7");
+
+        assert_eq!(
+            batch.warnings,
+            [Warning {
+                line: 8,
+                column: 22,
+                key: String::from("ecl")
+            }]
+        );
+        assert_eq!(
+            batch.warnings[0].to_string(),
+            "line 8, column 22: duplicate key 'ecl'"
+        );
+    }
+
     #[test]
     fn test_fields_valid() {
         let text = "\
@@ -185,77 +375,85 @@ hgt:179cm
 hcl:#cfa07d eyr:2025 pid:166559648
 iyr:2011 ecl:brn hgt:59in";
 
-        let passport_list = Passport::parse_text(text);
+        let passport_list = Passport::parse_text(text).unwrap();
         let mut passport_list = passport_list.iter();
 
-        assert_eq!(passport_list.next().unwrap().fields_valid(), true);
-        assert_eq!(passport_list.next().unwrap().fields_valid(), false);
-        assert_eq!(passport_list.next().unwrap().fields_valid(), true);
-        assert_eq!(passport_list.next().unwrap().fields_valid(), false);
+        assert!(passport_list.next().unwrap().fields_valid());
+        assert!(!passport_list.next().unwrap().fields_valid());
+        assert!(passport_list.next().unwrap().fields_valid());
+        assert!(!passport_list.next().unwrap().fields_valid());
     }
 
     #[test]
     fn test_birth_year_valid() {
-        let mut passport = Passport::default();
-
-        passport.birth_year = Some(String::from("2002"));
-        assert_eq!(passport.birth_year_valid(), true);
+        let mut passport = Passport {
+            birth_year: Some(String::from("2002")),
+            ..Default::default()
+        };
+        assert!(passport.birth_year_valid());
 
         passport.birth_year = Some(String::from("2003"));
-        assert_eq!(passport.birth_year_valid(), false);
+        assert!(!passport.birth_year_valid());
     }
 
     #[test]
     fn test_height_valid() {
-        let mut passport = Passport::default();
-
-        passport.height = Some(String::from("60in"));
-        assert_eq!(passport.height_valid(), true);
+        let mut passport = Passport {
+            height: Some(String::from("60in")),
+            ..Default::default()
+        };
+        assert!(passport.height_valid());
 
         passport.height = Some(String::from("190cm"));
-        assert_eq!(passport.height_valid(), true);
+        assert!(passport.height_valid());
 
         passport.height = Some(String::from("190in"));
-        assert_eq!(passport.height_valid(), false);
+        assert!(!passport.height_valid());
 
         passport.height = Some(String::from("190"));
-        assert_eq!(passport.height_valid(), false);
+        assert!(!passport.height_valid());
+
+        passport.height = Some(String::from("in"));
+        assert!(!passport.height_valid());
     }
 
     #[test]
     fn test_hair_color_valid() {
-        let mut passport = Passport::default();
-
-        passport.hair_color = Some(String::from("#123abc"));
-        assert_eq!(passport.hair_color_valid(), true);
+        let mut passport = Passport {
+            hair_color: Some(String::from("#123abc")),
+            ..Default::default()
+        };
+        assert!(passport.hair_color_valid());
 
         passport.hair_color = Some(String::from("#123abz"));
-        assert_eq!(passport.hair_color_valid(), false);
+        assert!(!passport.hair_color_valid());
 
         passport.hair_color = Some(String::from("123abc"));
-        assert_eq!(passport.hair_color_valid(), false);
+        assert!(!passport.hair_color_valid());
     }
 
     #[test]
     fn test_eye_color_valid() {
-        let mut passport = Passport::default();
-
-        passport.eye_color = Some(String::from("brn"));
-        assert_eq!(passport.eye_color_valid(), true);
+        let mut passport = Passport {
+            eye_color: Some(String::from("brn")),
+            ..Default::default()
+        };
+        assert!(passport.eye_color_valid());
 
         passport.eye_color = Some(String::from("wat"));
-        assert_eq!(passport.eye_color_valid(), false);
+        assert!(!passport.eye_color_valid());
     }
 
     #[test]
     fn test_passport_id_valid() {
-        let mut passport = Passport::default();
-
-        passport.passport_id = Some(String::from("000000001"));
-        assert_eq!(passport.passport_id_valid(), true);
+        let mut passport = Passport {
+            passport_id: Some(String::from("000000001")),
+            ..Default::default()
+        };
+        assert!(passport.passport_id_valid());
 
         passport.passport_id = Some(String::from("0123456789"));
-        assert_eq!(passport.passport_id_valid(), false);
+        assert!(!passport.passport_id_valid());
     }
 
     #[test]
@@ -275,13 +473,13 @@ hgt:59cm ecl:zzz
 eyr:2038 hcl:74454a iyr:2023
 pid:3556412378 byr:2007";
 
-        let passport_list = Passport::parse_text(text);
+        let passport_list = Passport::parse_text(text).unwrap();
         let mut passport_list = passport_list.iter();
 
-        assert_eq!(passport_list.next().unwrap().data_valid(), false);
-        assert_eq!(passport_list.next().unwrap().data_valid(), false);
-        assert_eq!(passport_list.next().unwrap().data_valid(), false);
-        assert_eq!(passport_list.next().unwrap().data_valid(), false);
+        assert!(!passport_list.next().unwrap().data_valid());
+        assert!(!passport_list.next().unwrap().data_valid());
+        assert!(!passport_list.next().unwrap().data_valid());
+        assert!(!passport_list.next().unwrap().data_valid());
     }
 
     #[test]
@@ -300,12 +498,12 @@ eyr:2022
 
 iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
 
-        let passport_list = Passport::parse_text(text);
+        let passport_list = Passport::parse_text(text).unwrap();
         let mut passport_list = passport_list.iter();
 
-        assert_eq!(passport_list.next().unwrap().data_valid(), true);
-        assert_eq!(passport_list.next().unwrap().data_valid(), true);
-        assert_eq!(passport_list.next().unwrap().data_valid(), true);
-        assert_eq!(passport_list.next().unwrap().data_valid(), true);
+        assert!(passport_list.next().unwrap().data_valid());
+        assert!(passport_list.next().unwrap().data_valid());
+        assert!(passport_list.next().unwrap().data_valid());
+        assert!(passport_list.next().unwrap().data_valid());
     }
 }
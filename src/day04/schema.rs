@@ -0,0 +1,327 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::{Field, Passport};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Rule {
+    /// Any value is accepted, as long as the field is present.
+    Present,
+    /// A four digit year within the range.
+    Year(RangeInclusive<u32>),
+    /// A number directly followed by one of the units, within that unit's range.
+    UnitRange(Vec<(&'static str, RangeInclusive<u32>)>),
+    /// A `#` followed by exactly six lowercase hexadecimal digits.
+    HexColour,
+    OneOf(Vec<&'static str>),
+    /// Exactly this many decimal digits, leading zeroes included.
+    Digits(usize),
+}
+
+fn format_range(range: &RangeInclusive<u32>) -> String {
+    format!("{}-{}", range.start(), range.end())
+}
+
+impl Rule {
+    /// Returns why `value` does not satisfy this rule.
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        match self {
+            Rule::Present => Ok(()),
+            Rule::Year(range) => {
+                if value.len() != 4 || !value.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(String::from("is not a four digit year"));
+                }
+                let year: u32 = value.parse().unwrap();
+                if range.contains(&year) {
+                    Ok(())
+                } else {
+                    Err(format!("is not between {}", format_range(range)))
+                }
+            }
+            Rule::UnitRange(units) => {
+                let digits = value.chars().take_while(|c| c.is_ascii_digit()).count();
+                let (number, unit) = value.split_at(digits);
+                let (_, range) = units
+                    .iter()
+                    .find(|(name, _)| *name == unit)
+                    .ok_or_else(|| {
+                        let names: Vec<&str> = units.iter().map(|(name, _)| *name).collect();
+                        format!("does not end in {}", names.join(" or "))
+                    })?;
+                let number: u32 = number
+                    .parse()
+                    .map_err(|_| format!("has no number before '{}'", unit))?;
+                if range.contains(&number) {
+                    Ok(())
+                } else {
+                    Err(format!("is not between {}{}", format_range(range), unit))
+                }
+            }
+            Rule::HexColour => {
+                let valid = value.strip_prefix('#').is_some_and(|digits| {
+                    digits.len() == 6 && digits.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+                });
+                if valid {
+                    Ok(())
+                } else {
+                    Err(String::from("is not a hex colour like #a1b2c3"))
+                }
+            }
+            Rule::OneOf(values) => {
+                if values.contains(&value) {
+                    Ok(())
+                } else {
+                    Err(format!("is not one of {}", values.join(", ")))
+                }
+            }
+            Rule::Digits(length) => {
+                if value.len() == *length && value.chars().all(|c| c.is_ascii_digit()) {
+                    Ok(())
+                } else {
+                    Err(format!("is not a {} digit number", length))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Failure {
+    Missing,
+    Invalid { value: String, reason: String },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldError {
+    pub field: Field,
+    pub failure: Failure,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.failure {
+            Failure::Missing => write!(f, "{}: missing", self.field.key()),
+            Failure::Invalid { value, reason } => {
+                write!(f, "{}: '{}' {}", self.field.key(), value, reason)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Report {
+    pub errors: Vec<FieldError>,
+}
+
+impl Report {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "valid");
+        }
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// The required fields of a passport and the rule each of them must satisfy.
+/// Fields without a rule are optional and never checked.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Schema {
+    rules: Vec<(Field, Rule)>,
+}
+
+impl Schema {
+    pub fn new() -> Schema {
+        Schema::default()
+    }
+
+    pub fn field(mut self, field: Field, rule: Rule) -> Schema {
+        self.rules.retain(|(existing, _)| *existing != field);
+        self.rules.push((field, rule));
+        self
+    }
+
+    /// All fields except `cid` must be present.
+    pub fn part1() -> Schema {
+        Field::ALL
+            .iter()
+            .filter(|&&field| field != Field::CountryId)
+            .fold(Schema::new(), |schema, &field| {
+                schema.field(field, Rule::Present)
+            })
+    }
+
+    /// All fields except `cid` must be present and hold valid data.
+    pub fn part2() -> Schema {
+        Schema::new()
+            .field(Field::BirthYear, Rule::Year(1920..=2002))
+            .field(Field::IssueYear, Rule::Year(2010..=2020))
+            .field(Field::ExpirationYear, Rule::Year(2020..=2030))
+            .field(
+                Field::Height,
+                Rule::UnitRange(vec![("cm", 150..=193), ("in", 59..=76)]),
+            )
+            .field(Field::HairColor, Rule::HexColour)
+            .field(
+                Field::EyeColor,
+                Rule::OneOf(vec!["amb", "blu", "brn", "gry", "grn", "hzl", "oth"]),
+            )
+            .field(Field::PassportId, Rule::Digits(9))
+    }
+
+    pub fn check_field(&self, passport: &Passport, field: Field) -> Option<FieldError> {
+        let (_, rule) = self.rules.iter().find(|(existing, _)| *existing == field)?;
+        let failure = match passport.get(field) {
+            None => Failure::Missing,
+            Some(value) => Failure::Invalid {
+                value: String::from(value),
+                reason: rule.check(value).err()?,
+            },
+        };
+        Some(FieldError { field, failure })
+    }
+
+    pub fn validate(&self, passport: &Passport) -> Report {
+        Report {
+            errors: self
+                .rules
+                .iter()
+                .filter_map(|(field, _)| self.check_field(passport, *field))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let year = Rule::Year(1920..=2002);
+        assert_eq!(year.check("2002"), Ok(()));
+        assert_eq!(
+            year.check("2003"),
+            Err(String::from("is not between 1920-2002"))
+        );
+        assert!(year.check("02002").is_err());
+
+        let height = Rule::UnitRange(vec![("cm", 150..=193), ("in", 59..=76)]);
+        assert_eq!(height.check("60in"), Ok(()));
+        assert_eq!(height.check("190cm"), Ok(()));
+        assert_eq!(
+            height.check("190in"),
+            Err(String::from("is not between 59-76in"))
+        );
+        assert_eq!(
+            height.check("190"),
+            Err(String::from("does not end in cm or in"))
+        );
+        assert_eq!(
+            height.check("in"),
+            Err(String::from("has no number before 'in'"))
+        );
+        assert_eq!(
+            height.check("c"),
+            Err(String::from("does not end in cm or in"))
+        );
+
+        assert_eq!(Rule::HexColour.check("#123abc"), Ok(()));
+        assert!(Rule::HexColour.check("#123abz").is_err());
+        assert!(Rule::HexColour.check("#123abcd").is_err());
+        assert!(Rule::HexColour.check("123abc").is_err());
+
+        assert_eq!(Rule::Digits(9).check("000000001"), Ok(()));
+        assert!(Rule::Digits(9).check("0123456789").is_err());
+        assert!(Rule::Digits(9).check("01234567a").is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let passport = Passport {
+            birth_year: Some(String::from("2003")),
+            height: Some(String::from("190")),
+            hair_color: Some(String::from("#123abc")),
+            eye_color: Some(String::from("brn")),
+            passport_id: Some(String::from("000000001")),
+            issue_year: Some(String::from("2015")),
+            ..Default::default()
+        };
+
+        let report = Schema::part2().validate(&passport);
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.errors,
+            [
+                FieldError {
+                    field: Field::BirthYear,
+                    failure: Failure::Invalid {
+                        value: String::from("2003"),
+                        reason: String::from("is not between 1920-2002")
+                    }
+                },
+                FieldError {
+                    field: Field::ExpirationYear,
+                    failure: Failure::Missing
+                },
+                FieldError {
+                    field: Field::Height,
+                    failure: Failure::Invalid {
+                        value: String::from("190"),
+                        reason: String::from("does not end in cm or in")
+                    }
+                },
+            ]
+        );
+        assert_eq!(
+            report.to_string(),
+            "byr: '2003' is not between 1920-2002\neyr: missing\nhgt: '190' does not end in cm or in"
+        );
+
+        let report = Schema::part1().validate(&passport);
+        assert_eq!(report.to_string(), "eyr: missing");
+    }
+
+    #[test]
+    fn test_custom_schema() {
+        let schema = Schema::part2().field(Field::CountryId, Rule::Digits(3));
+        let passport = Passport {
+            country_id: Some(String::from("12")),
+            ..Default::default()
+        };
+
+        let report = schema.validate(&passport);
+        assert_eq!(report.errors.len(), 8);
+        assert_eq!(
+            report.errors.last().unwrap().to_string(),
+            "cid: '12' is not a 3 digit number"
+        );
+    }
+}
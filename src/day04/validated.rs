@@ -0,0 +1,222 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::{Field, Passport, Report, Schema};
+use std::convert::TryFrom;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HeightUnit {
+    Cm,
+    In,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Height {
+    pub value: u16,
+    pub unit: HeightUnit,
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match self.unit {
+            HeightUnit::Cm => "cm",
+            HeightUnit::In => "in",
+        };
+        write!(f, "{}{}", self.value, unit)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rgb {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EyeColor {
+    Amber,
+    Blue,
+    Brown,
+    Gray,
+    Green,
+    Hazel,
+    Other,
+}
+
+impl EyeColor {
+    pub fn from_code(code: &str) -> Option<EyeColor> {
+        match code {
+            "amb" => Some(EyeColor::Amber),
+            "blu" => Some(EyeColor::Blue),
+            "brn" => Some(EyeColor::Brown),
+            "gry" => Some(EyeColor::Gray),
+            "grn" => Some(EyeColor::Green),
+            "hzl" => Some(EyeColor::Hazel),
+            "oth" => Some(EyeColor::Other),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            EyeColor::Amber => "amb",
+            EyeColor::Blue => "blu",
+            EyeColor::Brown => "brn",
+            EyeColor::Gray => "gry",
+            EyeColor::Green => "grn",
+            EyeColor::Hazel => "hzl",
+            EyeColor::Other => "oth",
+        }
+    }
+}
+
+/// A nine digit passport id; leading zeroes are kept when formatting.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct PassportId(pub u32);
+
+impl fmt::Display for PassportId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:09}", self.0)
+    }
+}
+
+/// A passport whose fields passed `Schema::part2`, with every field parsed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidatedPassport {
+    pub birth_year: u16,
+    pub issue_year: u16,
+    pub expiration_year: u16,
+    pub height: Height,
+    pub hair_color: Rgb,
+    pub eye_color: EyeColor,
+    pub passport_id: PassportId,
+    pub country_id: Option<String>,
+}
+
+impl TryFrom<&Passport> for ValidatedPassport {
+    type Error = Report;
+
+    fn try_from(passport: &Passport) -> Result<ValidatedPassport, Report> {
+        let report = Schema::part2().validate(passport);
+        if !report.is_valid() {
+            return Err(report);
+        }
+
+        // The schema guarantees every required field is present and well formed.
+        let value = |field| passport.get(field).unwrap();
+        let year = |field| value(field).parse().unwrap();
+        let height = value(Field::Height);
+        let (number, unit) = height.split_at(height.len() - 2);
+        let hair_color = &value(Field::HairColor)[1..];
+        let channel = |index: usize| u8::from_str_radix(&hair_color[index..index + 2], 16).unwrap();
+
+        Ok(ValidatedPassport {
+            birth_year: year(Field::BirthYear),
+            issue_year: year(Field::IssueYear),
+            expiration_year: year(Field::ExpirationYear),
+            height: Height {
+                value: number.parse().unwrap(),
+                unit: if unit == "cm" {
+                    HeightUnit::Cm
+                } else {
+                    HeightUnit::In
+                },
+            },
+            hair_color: Rgb {
+                red: channel(0),
+                green: channel(2),
+                blue: channel(4),
+            },
+            eye_color: EyeColor::from_code(value(Field::EyeColor)).unwrap(),
+            passport_id: PassportId(value(Field::PassportId).parse().unwrap()),
+            country_id: passport.country_id.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from() {
+        let text = "\
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f
+
+eyr:2029 ecl:blu cid:129 byr:1989
+iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm";
+
+        let passports = Passport::parse_text(text).unwrap();
+
+        let passport = ValidatedPassport::try_from(&passports[0]).unwrap();
+        assert_eq!(passport.birth_year, 1980);
+        assert_eq!(passport.issue_year, 2012);
+        assert_eq!(passport.expiration_year, 2030);
+        assert_eq!(
+            passport.height,
+            Height {
+                value: 74,
+                unit: HeightUnit::In
+            }
+        );
+        assert_eq!(
+            passport.hair_color,
+            Rgb {
+                red: 0x62,
+                green: 0x3a,
+                blue: 0x2f
+            }
+        );
+        assert_eq!(passport.eye_color, EyeColor::Green);
+        assert_eq!(passport.passport_id, PassportId(87499704));
+        assert_eq!(passport.country_id, None);
+
+        assert_eq!(passport.height.to_string(), "74in");
+        assert_eq!(passport.hair_color.to_string(), "#623a2f");
+        assert_eq!(passport.eye_color.code(), "grn");
+        assert_eq!(passport.passport_id.to_string(), "087499704");
+
+        let passport = ValidatedPassport::try_from(&passports[1]).unwrap();
+        assert_eq!(passport.height.unit, HeightUnit::Cm);
+        assert_eq!(passport.country_id.as_deref(), Some("129"));
+    }
+
+    #[test]
+    fn test_try_from_invalid() {
+        let text = "\
+eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926";
+
+        let passports = Passport::parse_text(text).unwrap();
+        let report = ValidatedPassport::try_from(&passports[0]).unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            "eyr: '1972' is not between 2020-2030\n\
+             hgt: '170' does not end in cm or in\n\
+             pid: '186cm' is not a 9 digit number"
+        );
+    }
+}
@@ -15,46 +15,372 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use std::fmt;
 use std::string::String;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BoardingPassError {
+    WrongLength {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidLetter {
+        position: usize,
+        letter: char,
+    },
+    SeatOutOfRange {
+        row: u32,
+        column: u32,
+    },
+    LayoutTooLarge {
+        row_bits: u32,
+        col_bits: u32,
+    },
+    LayoutMismatch {
+        expected: BoardingPassLayout,
+        actual: BoardingPassLayout,
+    },
+}
+
+impl fmt::Display for BoardingPassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardingPassError::WrongLength { expected, actual } => write!(
+                f,
+                "boarding pass has {} letters, expected {}",
+                actual, expected
+            ),
+            BoardingPassError::InvalidLetter { position, letter } => {
+                write!(
+                    f,
+                    "invalid letter '{}' at position {}",
+                    letter,
+                    position + 1
+                )
+            }
+            BoardingPassError::SeatOutOfRange { row, column } => {
+                write!(f, "row {}, column {} is outside of the plane", row, column)
+            }
+            BoardingPassError::LayoutTooLarge { row_bits, col_bits } => write!(
+                f,
+                "{} row and {} column letters do not fit a seat id",
+                row_bits, col_bits
+            ),
+            BoardingPassError::LayoutMismatch { expected, actual } => write!(
+                f,
+                "boarding pass has {} row and {} column letters, expected {} and {}",
+                actual.row_bits, actual.col_bits, expected.row_bits, expected.col_bits
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BoardingPassError {}
+
+/// The number of row (F/B) and column (L/R) letters on a boarding pass. Every
+/// letter is a binary digit, with B and R being one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct BoardingPassLayout {
+    row_bits: u32,
+    col_bits: u32,
+}
+
+impl BoardingPassLayout {
+    pub const STANDARD: BoardingPassLayout = BoardingPassLayout {
+        row_bits: 7,
+        col_bits: 3,
+    };
+
+    /// Fails if the seat ids of the layout do not fit in 31 bits.
+    pub fn new(row_bits: u32, col_bits: u32) -> Result<BoardingPassLayout, BoardingPassError> {
+        match row_bits.checked_add(col_bits) {
+            Some(bits) if bits < 32 => Ok(BoardingPassLayout { row_bits, col_bits }),
+            _ => Err(BoardingPassError::LayoutTooLarge { row_bits, col_bits }),
+        }
+    }
+
+    pub fn row_bits(&self) -> u32 {
+        self.row_bits
+    }
+
+    pub fn col_bits(&self) -> u32 {
+        self.col_bits
+    }
+
+    pub fn pass_length(&self) -> usize {
+        (self.row_bits + self.col_bits) as usize
+    }
+
+    pub fn rows(&self) -> u32 {
+        1 << self.row_bits
+    }
+
+    pub fn columns(&self) -> u32 {
+        1 << self.col_bits
+    }
+
+    pub fn decode(&self, pass: &str) -> Result<(u32, u32), BoardingPassError> {
+        let actual = pass.chars().count();
+        if actual != self.pass_length() {
+            return Err(BoardingPassError::WrongLength {
+                expected: self.pass_length(),
+                actual,
+            });
+        }
+
+        let mut row = 0;
+        let mut column = 0;
+        for (position, letter) in pass.chars().enumerate() {
+            let (value, bit) = match (position < self.row_bits as usize, letter) {
+                (true, 'F') => (&mut row, 0),
+                (true, 'B') => (&mut row, 1),
+                (false, 'L') => (&mut column, 0),
+                (false, 'R') => (&mut column, 1),
+                _ => return Err(BoardingPassError::InvalidLetter { position, letter }),
+            };
+            *value = *value << 1 | bit;
+        }
+        Ok((row, column))
+    }
+
+    pub fn encode(&self, row: u32, column: u32) -> Result<String, BoardingPassError> {
+        if row >= self.rows() || column >= self.columns() {
+            return Err(BoardingPassError::SeatOutOfRange { row, column });
+        }
+        let row_letters = (0..self.row_bits)
+            .rev()
+            .map(|bit| if row >> bit & 1 == 1 { 'B' } else { 'F' });
+        let column_letters =
+            (0..self.col_bits)
+                .rev()
+                .map(|bit| if column >> bit & 1 == 1 { 'R' } else { 'L' });
+        Ok(row_letters.chain(column_letters).collect())
+    }
+
+    pub fn seat_id(&self, row: u32, column: u32) -> u32 {
+        row << self.col_bits | column
+    }
+
+    pub fn from_seat_id(&self, seat_id: u32) -> Result<(u32, u32), BoardingPassError> {
+        let row = seat_id >> self.col_bits;
+        let column = seat_id & (self.columns() - 1);
+        if row >= self.rows() {
+            return Err(BoardingPassError::SeatOutOfRange { row, column });
+        }
+        Ok((row, column))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct SeatNumber {
     seat_number: String,
+    layout: BoardingPassLayout,
 }
 
 impl SeatNumber {
-    pub fn from(text: &str) -> SeatNumber {
-        SeatNumber {
-            seat_number: String::from(text),
-        }
+    pub fn parse(text: &str) -> Result<SeatNumber, ParseError> {
+        SeatNumber::parse_with_layout(BoardingPassLayout::STANDARD, text)
     }
 
-    fn accumulator(acc: (u32, u32), c: char) -> (u32, u32) {
-        let average = (acc.0 + acc.1) / 2;
-        if c == 'F' || c == 'L' {
-            (acc.0, average)
-        } else if c == 'B' || c == 'R' {
-            (average + 1, acc.1)
-        } else {
-            panic!()
+    pub fn parse_with_layout(
+        layout: BoardingPassLayout,
+        text: &str,
+    ) -> Result<SeatNumber, ParseError> {
+        match layout.decode(text) {
+            Ok(_) => Ok(SeatNumber {
+                seat_number: String::from(text),
+                layout,
+            }),
+            Err(BoardingPassError::InvalidLetter { position, .. }) => {
+                let (i, c) = text.char_indices().nth(position).unwrap();
+                Err(ParseError::in_line(
+                    ParseErrorKind::InvalidCharacter,
+                    1,
+                    text,
+                    &text[i..i + c.len_utf8()],
+                ))
+            }
+            Err(_) => Err(ParseError::new(ParseErrorKind::InvalidLength, 1, 1, text)),
         }
     }
 
+    pub fn from_seat_id(
+        layout: BoardingPassLayout,
+        seat_id: u32,
+    ) -> Result<SeatNumber, BoardingPassError> {
+        let (row, column) = layout.from_seat_id(seat_id)?;
+        Ok(SeatNumber {
+            seat_number: layout.encode(row, column)?,
+            layout,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.seat_number
+    }
+
     pub fn get_row_column(&self) -> (u32, u32) {
-        let row = self.seat_number.as_str()[..7]
-            .chars()
-            .fold((0, 127), Self::accumulator);
-        assert_eq!(row.0, row.1);
-        let column = self.seat_number.as_str()[7..]
-            .chars()
-            .fold((0, 7), Self::accumulator);
-        assert_eq!(column.0, column.1);
-        (row.0, column.0)
+        self.layout.decode(&self.seat_number).unwrap()
     }
 
     pub fn get_seat_id(&self) -> u32 {
         let (row, column) = self.get_row_column();
-        row * 8 + column
+        self.layout.seat_id(row, column)
+    }
+}
+
+/// Seat occupancy of a flight, stored as one bit per seat id.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Flight {
+    layout: BoardingPassLayout,
+    occupied: Vec<u64>,
+    duplicates: Vec<u32>,
+}
+
+impl Flight {
+    /// Fails if a boarding pass was read with a different layout or does not
+    /// fit on the plane.
+    pub fn new(
+        layout: BoardingPassLayout,
+        seat_numbers: &[SeatNumber],
+    ) -> Result<Flight, BoardingPassError> {
+        let seat_count = (layout.rows() * layout.columns()) as usize;
+        let mut flight = Flight {
+            layout,
+            occupied: vec![0; seat_count.div_ceil(64)],
+            duplicates: vec![],
+        };
+        for seat_number in seat_numbers {
+            if seat_number.layout != layout {
+                return Err(BoardingPassError::LayoutMismatch {
+                    expected: layout,
+                    actual: seat_number.layout,
+                });
+            }
+            let (row, column) = seat_number.get_row_column();
+            let seat_id = layout.seat_id(row, column);
+            if seat_id >= flight.seat_count() {
+                return Err(BoardingPassError::SeatOutOfRange { row, column });
+            }
+            if flight.is_occupied(seat_id) {
+                flight.duplicates.push(seat_id);
+            }
+            flight.occupied[seat_id as usize / 64] |= 1 << (seat_id % 64);
+        }
+        flight.duplicates.sort_unstable();
+        flight.duplicates.dedup();
+        Ok(flight)
+    }
+
+    fn seat_count(&self) -> u32 {
+        self.layout.rows() * self.layout.columns()
+    }
+
+    pub fn is_occupied(&self, seat_id: u32) -> bool {
+        seat_id < self.seat_count()
+            && self.occupied[seat_id as usize / 64] & 1 << (seat_id % 64) != 0
+    }
+
+    pub fn occupied_count(&self) -> usize {
+        self.occupied
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn missing_seats(&self) -> Vec<u32> {
+        (0..self.seat_count())
+            .filter(|&seat_id| !self.is_occupied(seat_id))
+            .collect()
+    }
+
+    /// Free seats whose seat ids directly before and after are both taken.
+    pub fn free_seats_between_neighbours(&self) -> Vec<u32> {
+        (1..self.seat_count().saturating_sub(1))
+            .filter(|&seat_id| {
+                !self.is_occupied(seat_id)
+                    && self.is_occupied(seat_id - 1)
+                    && self.is_occupied(seat_id + 1)
+            })
+            .collect()
+    }
+
+    fn row_is_empty(&self, row: u32) -> bool {
+        (0..self.layout.columns()).all(|column| !self.is_occupied(self.layout.seat_id(row, column)))
+    }
+
+    /// The number of completely empty rows at the front of the plane.
+    pub fn front_gap(&self) -> u32 {
+        (0..self.layout.rows())
+            .take_while(|&row| self.row_is_empty(row))
+            .count() as u32
+    }
+
+    /// The number of completely empty rows at the back of the plane.
+    pub fn back_gap(&self) -> u32 {
+        (0..self.layout.rows())
+            .rev()
+            .take_while(|&row| self.row_is_empty(row))
+            .count() as u32
+    }
+
+    /// Seat ids for which more than one boarding pass was scanned.
+    pub fn duplicates(&self) -> &[u32] {
+        &self.duplicates
+    }
+
+    /// Draws one line per row, with `#` for taken seats, `.` for free seats
+    /// and `!` for seats with duplicate boarding passes.
+    pub fn render(&self) -> String {
+        let width = (self.layout.rows() - 1).to_string().len();
+        let mut text = String::new();
+        for row in 0..self.layout.rows() {
+            text.push_str(&format!("{:>width$} ", row, width = width));
+            for column in 0..self.layout.columns() {
+                let seat_id = self.layout.seat_id(row, column);
+                text.push(if self.duplicates.binary_search(&seat_id).is_ok() {
+                    '!'
+                } else if self.is_occupied(seat_id) {
+                    '#'
+                } else {
+                    '.'
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+pub struct Day05;
+
+impl Solver for Day05 {
+    type Input = Vec<SeatNumber>;
+
+    fn parse(text: &str) -> Result<Vec<SeatNumber>, ParseError> {
+        text.lines()
+            .enumerate()
+            .map(|(i, line)| SeatNumber::parse(line).map_err(|error| error.at_line(i + 1)))
+            .collect()
+    }
+
+    fn part1(seat_numbers: &Vec<SeatNumber>) -> String {
+        match seat_numbers.iter().map(|sn| sn.get_seat_id()).max() {
+            Some(seat_id) => seat_id.to_string(),
+            None => String::from("No boarding passes"),
+        }
+    }
+
+    fn part2(seat_numbers: &Vec<SeatNumber>) -> String {
+        match Flight::new(BoardingPassLayout::STANDARD, seat_numbers) {
+            Ok(flight) => match flight.free_seats_between_neighbours().first() {
+                Some(seat_id) => seat_id.to_string(),
+                None => String::from("No free seat between two taken seats"),
+            },
+            Err(error) => error.to_string(),
+        }
     }
 }
 
@@ -64,16 +390,161 @@ mod tests {
 
     #[test]
     fn test_seat_number() {
-        let seat = SeatNumber::from("BFFFBBFRRR");
+        let seat = SeatNumber::parse("BFFFBBFRRR").unwrap();
         assert_eq!(seat.get_row_column(), (70, 7));
         assert_eq!(seat.get_seat_id(), 567);
 
-        let seat = SeatNumber::from("FFFBBBFRRR");
+        let seat = SeatNumber::parse("FFFBBBFRRR").unwrap();
         assert_eq!(seat.get_row_column(), (14, 7));
         assert_eq!(seat.get_seat_id(), 119);
 
-        let seat = SeatNumber::from("BBFFBBFRLL");
+        let seat = SeatNumber::parse("BBFFBBFRLL").unwrap();
         assert_eq!(seat.get_row_column(), (102, 4));
         assert_eq!(seat.get_seat_id(), 820);
     }
+
+    #[test]
+    fn test_layout() {
+        let layout = BoardingPassLayout::new(4, 2).unwrap();
+        assert_eq!(layout.pass_length(), 6);
+        assert_eq!(layout.decode("BFFBRL"), Ok((9, 2)));
+        assert_eq!(layout.seat_id(9, 2), 38);
+        assert_eq!(layout.from_seat_id(38), Ok((9, 2)));
+        assert_eq!(layout.encode(9, 2), Ok(String::from("BFFBRL")));
+
+        for seat_id in 0..64 {
+            let (row, column) = layout.from_seat_id(seat_id).unwrap();
+            let pass = layout.encode(row, column).unwrap();
+            let (row, column) = layout.decode(&pass).unwrap();
+            assert_eq!(layout.seat_id(row, column), seat_id);
+        }
+
+        let seat = SeatNumber::from_seat_id(BoardingPassLayout::STANDARD, 820).unwrap();
+        assert_eq!(seat.as_str(), "BBFFBBFRLL");
+        assert_eq!(seat.get_seat_id(), 820);
+
+        let seat = SeatNumber::parse_with_layout(layout, "FBBBLR").unwrap();
+        assert_eq!(seat.get_row_column(), (7, 1));
+        assert_eq!(seat.get_seat_id(), 29);
+    }
+
+    #[test]
+    fn test_layout_invalid() {
+        let layout = BoardingPassLayout::new(4, 2).unwrap();
+        assert_eq!(
+            layout.decode("BFFBR"),
+            Err(BoardingPassError::WrongLength {
+                expected: 6,
+                actual: 5
+            })
+        );
+        assert_eq!(
+            layout.decode("BFFRRL"),
+            Err(BoardingPassError::InvalidLetter {
+                position: 3,
+                letter: 'R'
+            })
+        );
+        assert_eq!(
+            layout.from_seat_id(64),
+            Err(BoardingPassError::SeatOutOfRange { row: 16, column: 0 })
+        );
+        assert_eq!(
+            layout.encode(3, 4),
+            Err(BoardingPassError::SeatOutOfRange { row: 3, column: 4 })
+        );
+        assert_eq!(
+            layout.decode("BFFRRL").unwrap_err().to_string(),
+            "invalid letter 'R' at position 4"
+        );
+
+        assert!(BoardingPassLayout::new(28, 3).is_ok());
+        assert_eq!(
+            BoardingPassLayout::new(40, 3),
+            Err(BoardingPassError::LayoutTooLarge {
+                row_bits: 40,
+                col_bits: 3
+            })
+        );
+        assert_eq!(
+            BoardingPassLayout::new(16, 16),
+            Err(BoardingPassError::LayoutTooLarge {
+                row_bits: 16,
+                col_bits: 16
+            })
+        );
+        assert!(BoardingPassLayout::new(u32::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn test_flight() {
+        let layout = BoardingPassLayout::new(2, 2).unwrap();
+        let seat_numbers: Vec<SeatNumber> = ["FBLL", "FBLR", "FBRR", "BFLL", "BFLR", "FBLR"]
+            .iter()
+            .map(|text| SeatNumber::parse_with_layout(layout, text).unwrap())
+            .collect();
+
+        let flight = Flight::new(layout, &seat_numbers).unwrap();
+        assert_eq!(flight.occupied_count(), 5);
+        assert!(flight.is_occupied(4));
+        assert!(!flight.is_occupied(6));
+        assert!(!flight.is_occupied(16));
+        assert_eq!(
+            flight.missing_seats(),
+            [0, 1, 2, 3, 6, 10, 11, 12, 13, 14, 15]
+        );
+        assert_eq!(flight.free_seats_between_neighbours(), [6]);
+        assert_eq!(flight.front_gap(), 1);
+        assert_eq!(flight.back_gap(), 1);
+        assert_eq!(flight.duplicates(), [5]);
+        assert_eq!(flight.render(), "0 ....\n1 #!.#\n2 ##..\n3 ....\n");
+    }
+
+    #[test]
+    fn test_flight_standard() {
+        let seat_numbers: Vec<SeatNumber> = (40..=60)
+            .filter(|&seat_id| seat_id != 50)
+            .map(|seat_id| SeatNumber::from_seat_id(BoardingPassLayout::STANDARD, seat_id).unwrap())
+            .collect();
+
+        let flight = Flight::new(BoardingPassLayout::STANDARD, &seat_numbers).unwrap();
+        assert_eq!(flight.free_seats_between_neighbours(), [50]);
+        assert_eq!(flight.front_gap(), 5);
+        assert_eq!(flight.back_gap(), 120);
+        assert!(flight.duplicates().is_empty());
+        assert_eq!(Day05::part2(&seat_numbers), "50");
+        assert_eq!(Day05::part1(&vec![]), "No boarding passes");
+        assert_eq!(
+            Day05::part2(&seat_numbers[..5].to_vec()),
+            "No free seat between two taken seats"
+        );
+        assert_eq!(
+            Flight::new(BoardingPassLayout::new(2, 2).unwrap(), &seat_numbers),
+            Err(BoardingPassError::LayoutMismatch {
+                expected: BoardingPassLayout::new(2, 2).unwrap(),
+                actual: BoardingPassLayout::STANDARD
+            })
+        );
+    }
+
+    #[test]
+    fn test_seat_number_invalid() {
+        assert_eq!(
+            SeatNumber::parse("BFFFBBFRR"),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidLength,
+                1,
+                1,
+                "BFFFBBFRR"
+            ))
+        );
+        assert_eq!(
+            SeatNumber::parse("BFFFBBFRXR"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 1, 9, "X"))
+        );
+        assert_eq!(
+            SeatNumber::parse("BFFFBBLRRR"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 1, 7, "L"))
+        );
+    }
 }
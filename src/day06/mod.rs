@@ -15,51 +15,174 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use itertools::Itertools;
-use nom::AsChar;
-use std::string::String;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use std::fmt;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
-pub struct DeclarationForm {
-    pub answers: String,
+pub mod stats;
+
+/// The questions answered with "yes", one bit per letter `a` to `z`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct AnswerSet(u32);
+
+impl AnswerSet {
+    pub const EMPTY: AnswerSet = AnswerSet(0);
+    pub const ALL: AnswerSet = AnswerSet((1 << 26) - 1);
+
+    fn bit(question: char) -> u32 {
+        1 << (question as u32 - 'a' as u32)
+    }
+
+    pub fn parse(line_number: usize, line: &str) -> Result<AnswerSet, ParseError> {
+        let mut answers = AnswerSet::EMPTY;
+        for (column, c) in line.char_indices() {
+            if !c.is_ascii_lowercase() {
+                return Err(ParseError::in_line(
+                    ParseErrorKind::InvalidCharacter,
+                    line_number,
+                    line,
+                    &line[column..column + c.len_utf8()],
+                ));
+            }
+            answers.0 |= Self::bit(c);
+        }
+        Ok(answers)
+    }
+
+    pub fn contains(self, question: char) -> bool {
+        question.is_ascii_lowercase() && self.0 & Self::bit(question) != 0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(self, other: AnswerSet) -> AnswerSet {
+        AnswerSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: AnswerSet) -> AnswerSet {
+        AnswerSet(self.0 & other.0)
+    }
+
+    pub fn symmetric_difference(self, other: AnswerSet) -> AnswerSet {
+        AnswerSet(self.0 ^ other.0)
+    }
+
+    pub fn questions(self) -> impl Iterator<Item = char> {
+        ('a'..='z').filter(move |&question| self.contains(question))
+    }
 }
 
-impl DeclarationForm {
-    pub fn parse_part1(text: &str) -> Vec<DeclarationForm> {
-        text.split("\n\n")
-            .map(|form_text| DeclarationForm {
-                answers: form_text
-                    .chars()
-                    .filter(|c| c.is_alpha())
-                    .unique()
-                    .collect(),
-            })
-            .collect()
-    }
-
-    pub fn parse_part2(text: &str) -> Vec<DeclarationForm> {
-        let possible_answers: String = ('a'..='z').collect();
-        text.split("\n\n")
-            .map(|form_text| {
-                let answers = form_text
-                    .lines()
-                    .fold(possible_answers.clone(), |answers, line| {
-                        answers.chars().filter(|c| line.contains(*c)).collect()
-                    });
-                DeclarationForm { answers }
+impl fmt::Display for AnswerSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.questions()
+            .try_for_each(|question| write!(f, "{}", question))
+    }
+}
+
+/// The answers of every person in a group, in the order they were written down.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Group {
+    pub people: Vec<AnswerSet>,
+}
+
+impl Group {
+    /// Questions answered by anyone in the group.
+    pub fn union(&self) -> AnswerSet {
+        self.people
+            .iter()
+            .fold(AnswerSet::EMPTY, |acc, &answers| acc.union(answers))
+    }
+
+    /// Questions answered by everyone in the group.
+    pub fn intersection(&self) -> AnswerSet {
+        if self.people.is_empty() {
+            return AnswerSet::EMPTY;
+        }
+        self.people
+            .iter()
+            .fold(AnswerSet::ALL, |acc, &answers| acc.intersection(answers))
+    }
+
+    /// Questions answered by an odd number of people in the group.
+    pub fn symmetric_difference(&self) -> AnswerSet {
+        self.people.iter().fold(AnswerSet::EMPTY, |acc, &answers| {
+            acc.symmetric_difference(answers)
+        })
+    }
+
+    /// The number of people that answered each question, `a` to `z`.
+    pub fn answer_counts(&self) -> [usize; 26] {
+        let mut counts = [0; 26];
+        for answers in &self.people {
+            for question in answers.questions() {
+                counts[(question as u8 - b'a') as usize] += 1;
+            }
+        }
+        counts
+    }
+
+    pub fn answered_by_at_least(&self, k: usize) -> AnswerSet {
+        let counts = self.answer_counts();
+        ('a'..='z')
+            .zip(counts.iter())
+            .filter(|(_, &count)| count >= k && count > 0)
+            .fold(AnswerSet::EMPTY, |acc, (question, _)| {
+                acc.union(AnswerSet(AnswerSet::bit(question)))
             })
-            .collect()
+    }
+}
+
+/// Parses groups separated by blank lines, with one person's answers per line.
+pub fn parse(text: &str) -> Result<Vec<Group>, ParseError> {
+    let mut groups = vec![];
+    let mut group = Group::default();
+    for (line_index, line) in text.lines().enumerate() {
+        if line.is_empty() {
+            if !group.people.is_empty() {
+                groups.push(group);
+            }
+            group = Group::default();
+        } else {
+            group.people.push(AnswerSet::parse(line_index + 1, line)?);
+        }
+    }
+    if !group.people.is_empty() {
+        groups.push(group);
+    }
+    Ok(groups)
+}
+
+pub struct Day06;
+
+impl Solver for Day06 {
+    type Input = Vec<Group>;
+
+    fn parse(text: &str) -> Result<Vec<Group>, ParseError> {
+        parse(text)
+    }
+
+    fn part1(groups: &Vec<Group>) -> String {
+        let sum: usize = groups.iter().map(|group| group.union().len()).sum();
+        sum.to_string()
+    }
+
+    fn part2(groups: &Vec<Group>) -> String {
+        let sum: usize = groups.iter().map(|group| group.intersection().len()).sum();
+        sum.to_string()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::ops::Index;
 
-    #[test]
-    fn test_parse_part1() {
-        let text = "\
+    const EXAMPLE: &str = "\
 abc
 
 a
@@ -75,41 +198,59 @@ a
 a
 
 b";
-        let forms = DeclarationForm::parse_part1(text);
 
-        assert_eq!(forms.len(), 5);
-        assert_eq!(forms.index(0).answers, "abc");
-        assert_eq!(forms.index(1).answers, "abc");
-        assert_eq!(forms.index(2).answers, "abc");
-        assert_eq!(forms.index(3).answers, "a");
-        assert_eq!(forms.index(4).answers, "b");
+    #[test]
+    fn test_union() {
+        let groups = parse(EXAMPLE).unwrap();
+
+        assert_eq!(groups.len(), 5);
+        assert_eq!(groups[0].union().to_string(), "abc");
+        assert_eq!(groups[1].union().to_string(), "abc");
+        assert_eq!(groups[2].union().to_string(), "abc");
+        assert_eq!(groups[3].union().to_string(), "a");
+        assert_eq!(groups[4].union().to_string(), "b");
+        assert_eq!(Day06::part1(&groups), "11");
     }
 
     #[test]
-    fn test_parse_part2() {
-        let text = "\
-abc
+    fn test_intersection() {
+        let groups = parse(EXAMPLE).unwrap();
 
-a
-b
-c
+        assert_eq!(groups[0].intersection().to_string(), "abc");
+        assert_eq!(groups[1].intersection().to_string(), "");
+        assert_eq!(groups[2].intersection().to_string(), "a");
+        assert_eq!(groups[3].intersection().to_string(), "a");
+        assert_eq!(groups[4].intersection().to_string(), "b");
+        assert_eq!(Day06::part2(&groups), "6");
+    }
 
-ab
-ac
+    #[test]
+    fn test_queries() {
+        let groups = parse("abc\nabd\nae").unwrap();
+        let group = &groups[0];
 
-a
-a
-a
-a
+        assert_eq!(group.people.len(), 3);
+        assert_eq!(group.people[1].to_string(), "abd");
+        assert_eq!(group.symmetric_difference().to_string(), "acde");
+        assert_eq!(group.answered_by_at_least(2).to_string(), "ab");
+        assert_eq!(group.answered_by_at_least(3), group.intersection());
+        assert_eq!(group.answered_by_at_least(0), group.union());
+        assert_eq!(group.answer_counts()[..5], [3, 2, 1, 1, 1]);
+        assert_eq!(group.union().len(), 5);
+        assert!(group.union().contains('e'));
+        assert!(!group.union().contains('f'));
+    }
 
-b";
-        let forms = DeclarationForm::parse_part2(text);
-
-        assert_eq!(forms.len(), 5);
-        assert_eq!(forms.index(0).answers, "abc");
-        assert_eq!(forms.index(1).answers, "");
-        assert_eq!(forms.index(2).answers, "a");
-        assert_eq!(forms.index(3).answers, "a");
-        assert_eq!(forms.index(4).answers, "b");
+    #[test]
+    fn test_parse_invalid() {
+        let text = "\
+abc
+
+aB";
+        let error = parse(text).err().unwrap();
+        assert_eq!(
+            error,
+            ParseError::new(ParseErrorKind::InvalidCharacter, 3, 2, "B")
+        );
     }
 }
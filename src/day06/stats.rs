@@ -0,0 +1,313 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::Group;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const HISTOGRAM_WIDTH: usize = 40;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct QuestionFrequency {
+    pub question: char,
+    pub people: usize,
+    pub groups: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GroupAgreement {
+    /// The 1-based position of the group in the input.
+    pub group: usize,
+    pub size: usize,
+    /// Questions answered by everyone divided by questions answered by anyone.
+    pub agreement: f64,
+    pub unanimous: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SizeAgreement {
+    pub size: usize,
+    pub groups: usize,
+    pub mean_agreement: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Statistics {
+    /// Sorted from most to least commonly answered.
+    pub questions: Vec<QuestionFrequency>,
+    pub groups: Vec<GroupAgreement>,
+    pub by_size: Vec<SizeAgreement>,
+    /// Groups whose agreement is more than two standard deviations from the mean.
+    pub outliers: Vec<usize>,
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+pub fn statistics(groups: &[Group]) -> Statistics {
+    let mut questions: Vec<QuestionFrequency> = ('a'..='z')
+        .map(|question| QuestionFrequency {
+            question,
+            people: 0,
+            groups: 0,
+        })
+        .collect();
+    for group in groups {
+        for (frequency, &count) in questions.iter_mut().zip(group.answer_counts().iter()) {
+            frequency.people += count;
+            frequency.groups += (count > 0) as usize;
+        }
+    }
+    questions.sort_by(|a, b| b.people.cmp(&a.people).then(a.question.cmp(&b.question)));
+
+    let agreements: Vec<GroupAgreement> = groups
+        .iter()
+        .enumerate()
+        .map(|(index, group)| {
+            let union = group.union();
+            let intersection = group.intersection();
+            GroupAgreement {
+                group: index + 1,
+                size: group.people.len(),
+                agreement: if union.is_empty() {
+                    1.0
+                } else {
+                    intersection.len() as f64 / union.len() as f64
+                },
+                unanimous: union == intersection,
+            }
+        })
+        .collect();
+
+    let mut sizes: BTreeMap<usize, Vec<f64>> = BTreeMap::new();
+    for agreement in &agreements {
+        sizes
+            .entry(agreement.size)
+            .or_default()
+            .push(agreement.agreement);
+    }
+    let by_size = sizes
+        .into_iter()
+        .map(|(size, values)| SizeAgreement {
+            size,
+            groups: values.len(),
+            mean_agreement: mean(&values),
+        })
+        .collect();
+
+    let values: Vec<f64> = agreements.iter().map(|a| a.agreement).collect();
+    let average = mean(&values);
+    let deviation = mean(
+        &values
+            .iter()
+            .map(|value| (value - average).powi(2))
+            .collect::<Vec<_>>(),
+    )
+    .sqrt();
+    let outliers = agreements
+        .iter()
+        .filter(|a| deviation > 0.0 && (a.agreement - average).abs() > 2.0 * deviation)
+        .map(|a| a.group)
+        .collect();
+
+    Statistics {
+        questions,
+        groups: agreements,
+        by_size,
+        outliers,
+    }
+}
+
+fn format_groups(groups: &[usize]) -> String {
+    if groups.is_empty() {
+        String::from("none")
+    } else {
+        groups
+            .iter()
+            .map(|group| group.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+pub fn format_table(statistics: &Statistics) -> String {
+    let max_people = statistics
+        .questions
+        .iter()
+        .map(|q| q.people)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let mut table = format!("{:<8}  {:>6}  {:>6}\n", "Question", "People", "Groups");
+    for frequency in &statistics.questions {
+        table += &format!(
+            "{:<8}  {:>6}  {:>6}  {}\n",
+            frequency.question,
+            frequency.people,
+            frequency.groups,
+            "#".repeat(frequency.people * HISTOGRAM_WIDTH / max_people)
+        );
+    }
+
+    table += &format!("\n{:>4}  {:>6}  {:>9}\n", "Size", "Groups", "Agreement");
+    for size in &statistics.by_size {
+        table += &format!(
+            "{:>4}  {:>6}  {:>9.3}\n",
+            size.size, size.groups, size.mean_agreement
+        );
+    }
+
+    let unanimous: Vec<usize> = statistics
+        .groups
+        .iter()
+        .filter(|a| a.unanimous)
+        .map(|a| a.group)
+        .collect();
+    table += &format!("\nUnanimous groups: {}\n", format_groups(&unanimous));
+    table += &format!("Outlier groups: {}\n", format_groups(&statistics.outliers));
+    table
+}
+
+pub fn format_json(statistics: &Statistics) -> String {
+    serde_json::to_string_pretty(statistics).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day06::parse;
+
+    #[test]
+    fn test_statistics() {
+        let text = "\
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+        let groups = parse(text).unwrap();
+        let statistics = statistics(&groups);
+
+        assert_eq!(
+            statistics.questions[..3],
+            [
+                QuestionFrequency {
+                    question: 'a',
+                    people: 8,
+                    groups: 4
+                },
+                QuestionFrequency {
+                    question: 'b',
+                    people: 4,
+                    groups: 4
+                },
+                QuestionFrequency {
+                    question: 'c',
+                    people: 3,
+                    groups: 3
+                },
+            ]
+        );
+        assert_eq!(statistics.questions[3].people, 0);
+
+        assert_eq!(statistics.groups[1].size, 3);
+        assert_eq!(statistics.groups[1].agreement, 0.0);
+        assert_eq!(statistics.groups[2].agreement, 1.0 / 3.0);
+        let unanimous: Vec<bool> = statistics.groups.iter().map(|a| a.unanimous).collect();
+        assert_eq!(unanimous, [true, false, false, true, true]);
+
+        assert_eq!(
+            statistics.by_size,
+            [
+                SizeAgreement {
+                    size: 1,
+                    groups: 2,
+                    mean_agreement: 1.0
+                },
+                SizeAgreement {
+                    size: 2,
+                    groups: 1,
+                    mean_agreement: 1.0 / 3.0
+                },
+                SizeAgreement {
+                    size: 3,
+                    groups: 1,
+                    mean_agreement: 0.0
+                },
+                SizeAgreement {
+                    size: 4,
+                    groups: 1,
+                    mean_agreement: 1.0
+                },
+            ]
+        );
+        assert!(statistics.outliers.is_empty());
+    }
+
+    #[test]
+    fn test_outliers() {
+        let mut text = String::new();
+        for _ in 0..10 {
+            text += "ab\nab\n\n";
+        }
+        text += "a\nb\n";
+        let statistics = statistics(&parse(&text).unwrap());
+        assert_eq!(statistics.outliers, [11]);
+    }
+
+    #[test]
+    fn test_format() {
+        let groups = parse("ab\nab\n\na\nb").unwrap();
+        let statistics = statistics(&groups);
+
+        let table = format_table(&statistics);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "Question  People  Groups");
+        assert_eq!(
+            lines[1],
+            format!("a              3       2  {}", "#".repeat(40))
+        );
+        assert_eq!(
+            lines[2],
+            format!("b              3       2  {}", "#".repeat(40))
+        );
+        assert_eq!(lines[3], "c              0       0  ");
+        assert!(table.ends_with(
+            "Size  Groups  Agreement\n   2       2      0.500\n\nUnanimous groups: 1\nOutlier groups: none\n"
+        ));
+
+        let json: serde_json::Value = serde_json::from_str(&format_json(&statistics)).unwrap();
+        assert_eq!(json["questions"][0]["question"], "a");
+        assert_eq!(json["groups"][1]["agreement"], 0.0);
+        assert_eq!(json["outliers"].as_array().unwrap().len(), 0);
+    }
+}
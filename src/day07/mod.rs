@@ -0,0 +1,339 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{NomError, ParseError, ParseErrorKind};
+use crate::Solver;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, char, digit1};
+use nom::combinator::{all_consuming, map, map_res, recognize};
+use nom::multi::separated_list1;
+use nom::sequence::tuple;
+use nom::IResult;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CycleError {
+    pub cycle: Vec<String>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bag rules contain a cycle: {}", self.cycle.join(" -> "))
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+type Rule<'a> = (&'a str, Vec<(usize, &'a str)>);
+
+fn color(i: &str) -> IResult<&str, &str, NomError<'_>> {
+    recognize(tuple((alpha1, char(' '), alpha1)))(i)
+}
+
+fn content(i: &str) -> IResult<&str, (usize, &str), NomError<'_>> {
+    let (i, count) = map_res(digit1, str::parse)(i)?;
+    let (i, _) = char(' ')(i)?;
+    let (i, color) = color(i)?;
+    let (i, _) = alt((tag(" bags"), tag(" bag")))(i)?;
+    Ok((i, (count, color)))
+}
+
+fn rule(i: &str) -> IResult<&str, Rule<'_>, NomError<'_>> {
+    let (i, container) = color(i)?;
+    let (i, _) = tag(" bags contain ")(i)?;
+    let (i, contents) = alt((
+        map(tag("no other bags"), |_| vec![]),
+        separated_list1(tag(", "), content),
+    ))(i)?;
+    let (i, _) = char('.')(i)?;
+    Ok((i, (container, contents)))
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BagGraph {
+    contents: BTreeMap<String, Vec<(usize, String)>>,
+    containers: BTreeMap<String, Vec<String>>,
+}
+
+impl BagGraph {
+    pub fn parse(text: &str) -> Result<BagGraph, ParseError> {
+        let mut graph = BagGraph::default();
+        for (line_index, line) in text.lines().enumerate() {
+            let (_, (container, contents)) = all_consuming(rule)(line)
+                .map_err(|error| ParseError::from_nom(line, error).at_line(line_index + 1))?;
+            if graph.contents.contains_key(container) {
+                return Err(ParseError::in_line(
+                    ParseErrorKind::DuplicateKey,
+                    line_index + 1,
+                    line,
+                    container,
+                ));
+            }
+            for (_, color) in &contents {
+                graph
+                    .containers
+                    .entry(String::from(*color))
+                    .or_default()
+                    .push(String::from(container));
+            }
+            graph.contents.insert(
+                String::from(container),
+                contents
+                    .into_iter()
+                    .map(|(count, color)| (count, String::from(color)))
+                    .collect(),
+            );
+        }
+        Ok(graph)
+    }
+
+    pub fn get_contents(&self, bag: &str) -> &[(usize, String)] {
+        self.contents.get(bag).map_or(&[], |contents| contents)
+    }
+
+    pub fn get_containers(&self, bag: &str) -> BTreeSet<&str> {
+        let mut containers = BTreeSet::new();
+        let mut queue = vec![bag];
+        while let Some(bag) = queue.pop() {
+            for container in self.containers.get(bag).into_iter().flatten() {
+                if containers.insert(container.as_str()) {
+                    queue.push(container);
+                }
+            }
+        }
+        containers
+    }
+
+    fn count_visit<'a>(
+        &'a self,
+        bag: &'a str,
+        path: &mut Vec<&'a str>,
+        counts: &mut BTreeMap<&'a str, usize>,
+    ) -> Result<usize, CycleError> {
+        if let Some(&count) = counts.get(bag) {
+            return Ok(count);
+        }
+        if let Some(start) = path.iter().position(|&visited| visited == bag) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|&b| String::from(b)).collect();
+            cycle.push(String::from(bag));
+            return Err(CycleError { cycle });
+        }
+
+        path.push(bag);
+        let mut count = 0;
+        for (inner_count, inner_bag) in self.get_contents(bag) {
+            count += inner_count * (1 + self.count_visit(inner_bag, path, counts)?);
+        }
+        path.pop();
+
+        counts.insert(bag, count);
+        Ok(count)
+    }
+
+    pub fn count_contained(&self, bag: &str) -> Result<usize, CycleError> {
+        self.count_visit(bag, &mut vec![], &mut BTreeMap::new())
+    }
+
+    pub fn check_acyclic(&self) -> Result<(), CycleError> {
+        let mut counts = BTreeMap::new();
+        for bag in self.contents.keys() {
+            self.count_visit(bag, &mut vec![], &mut counts)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Day07;
+
+impl Solver for Day07 {
+    type Input = BagGraph;
+
+    fn parse(text: &str) -> Result<BagGraph, ParseError> {
+        let graph = BagGraph::parse(text)?;
+        graph.check_acyclic().map_err(|error| {
+            let rule_start = format!("{} bags ", error.cycle[0]);
+            let line_index = text
+                .lines()
+                .position(|line| line.starts_with(&rule_start))
+                .unwrap_or(0);
+            ParseError::new(
+                ParseErrorKind::Cycle,
+                line_index + 1,
+                1,
+                &error.cycle.join(" -> "),
+            )
+        })?;
+        Ok(graph)
+    }
+
+    fn part1(graph: &BagGraph) -> String {
+        graph.get_containers("shiny gold").len().to_string()
+    }
+
+    fn part2(graph: &BagGraph) -> String {
+        graph
+            .count_contained("shiny gold")
+            .expect("cycles are rejected by Day07::parse")
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+    #[test]
+    fn test_parse() {
+        let graph = BagGraph::parse(EXAMPLE).unwrap();
+
+        assert_eq!(graph.contents.len(), 9);
+        assert_eq!(
+            graph.get_contents("light red"),
+            [
+                (1, String::from("bright white")),
+                (2, String::from("muted yellow"))
+            ]
+        );
+        assert_eq!(
+            graph.get_contents("bright white"),
+            [(1, String::from("shiny gold"))]
+        );
+        assert!(graph.get_contents("faded blue").is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let text = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain three bright white bags.";
+
+        assert_eq!(
+            BagGraph::parse(text),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidNumber,
+                2,
+                26,
+                "three"
+            ))
+        );
+
+        assert_eq!(
+            BagGraph::parse("faded blue bags contain no other bags"),
+            Err(ParseError::new(ParseErrorKind::UnexpectedEnd, 1, 38, ""))
+        );
+
+        let text = "\
+faded blue bags contain no other bags.
+light red bags contain 1 faded blue bag.
+faded blue bags contain 1 light red bag.";
+        assert_eq!(
+            BagGraph::parse(text),
+            Err(ParseError::new(
+                ParseErrorKind::DuplicateKey,
+                3,
+                1,
+                "faded blue"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_get_containers() {
+        let graph = BagGraph::parse(EXAMPLE).unwrap();
+
+        let containers: Vec<&str> = graph.get_containers("shiny gold").into_iter().collect();
+        assert_eq!(
+            containers,
+            ["bright white", "dark orange", "light red", "muted yellow"]
+        );
+        assert!(graph.get_containers("light red").is_empty());
+    }
+
+    #[test]
+    fn test_count_contained() {
+        let graph = BagGraph::parse(EXAMPLE).unwrap();
+        assert_eq!(graph.count_contained("shiny gold"), Ok(32));
+        assert_eq!(graph.count_contained("faded blue"), Ok(0));
+
+        let text = "\
+shiny gold bags contain 2 dark red bags.
+dark red bags contain 2 dark orange bags.
+dark orange bags contain 2 dark yellow bags.
+dark yellow bags contain 2 dark green bags.
+dark green bags contain 2 dark blue bags.
+dark blue bags contain 2 dark violet bags.
+dark violet bags contain no other bags.";
+        let graph = BagGraph::parse(text).unwrap();
+        assert_eq!(graph.count_contained("shiny gold"), Ok(126));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let text = "\
+shiny gold bags contain 2 dark red bags.
+dark red bags contain 1 dark orange bag, 1 faded blue bag.
+dark orange bags contain 1 shiny gold bag.
+faded blue bags contain no other bags.";
+        let graph = BagGraph::parse(text).unwrap();
+
+        let error = graph.count_contained("dark red").unwrap_err();
+        assert_eq!(
+            error.cycle,
+            ["dark red", "dark orange", "shiny gold", "dark red"]
+        );
+        assert_eq!(
+            error.to_string(),
+            "bag rules contain a cycle: dark red -> dark orange -> shiny gold -> dark red"
+        );
+        assert!(graph.check_acyclic().is_err());
+        assert_eq!(graph.count_contained("faded blue"), Ok(0));
+
+        let graph = BagGraph::parse(EXAMPLE).unwrap();
+        assert_eq!(graph.check_acyclic(), Ok(()));
+    }
+
+    #[test]
+    fn test_solver_rejects_cycle() {
+        let text = "\
+faded blue bags contain no other bags.
+dark red bags contain 1 dark orange bag, 1 faded blue bag.
+dark orange bags contain 1 dark red bag.";
+        assert_eq!(
+            Day07::parse(text).err(),
+            Some(ParseError::new(
+                ParseErrorKind::Cycle,
+                3,
+                1,
+                "dark orange -> dark red -> dark orange"
+            ))
+        );
+        assert!(Day07::parse(EXAMPLE).is_ok());
+    }
+}
@@ -0,0 +1,251 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::{Instruction, Opcode};
+use crate::error::{column_of, ParseError, ParseErrorKind};
+use std::collections::BTreeMap;
+
+/// An instruction in assembler source, before its label is resolved.
+struct Statement<'a> {
+    line_number: usize,
+    line: &'a str,
+    op: &'a str,
+    arg: Option<&'a str>,
+}
+
+fn is_label(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl<'a> Statement<'a> {
+    fn assemble(
+        &self,
+        index: usize,
+        labels: &BTreeMap<&str, usize>,
+    ) -> Result<Instruction, ParseError> {
+        let opcode = Opcode::find(self.op).ok_or_else(|| {
+            ParseError::in_line(
+                ParseErrorKind::UnknownInstruction,
+                self.line_number,
+                self.line,
+                self.op,
+            )
+        })?;
+        let arg = self.arg.ok_or_else(|| {
+            ParseError::new(
+                ParseErrorKind::UnexpectedEnd,
+                self.line_number,
+                column_of(self.line, self.op) + self.op.len(),
+                "",
+            )
+        })?;
+
+        let value = match arg.parse::<i32>() {
+            Ok(value) => value,
+            Err(_) if opcode.jump && is_label(arg) => {
+                let target = labels.get(arg).ok_or_else(|| {
+                    ParseError::in_line(
+                        ParseErrorKind::UnknownLabel,
+                        self.line_number,
+                        self.line,
+                        arg,
+                    )
+                })?;
+                *target as i32 - index as i32
+            }
+            Err(_) => {
+                return Err(ParseError::in_line(
+                    ParseErrorKind::InvalidNumber,
+                    self.line_number,
+                    self.line,
+                    arg,
+                ))
+            }
+        };
+        Ok((opcode.build)(value))
+    }
+}
+
+/// Assembles source text into a program. On top of the puzzle format, lines may start with a
+/// `label:`, which jumps can use instead of a relative offset, and anything after `;` is a
+/// comment.
+pub fn assemble(text: &str) -> Result<Vec<Instruction>, ParseError> {
+    let mut labels = BTreeMap::new();
+    let mut statements = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let mut code = line.split(';').next().unwrap();
+        if let Some(colon) = code.find(':') {
+            let label = code[..colon].trim();
+            if !is_label(label) {
+                return Err(ParseError::in_line(
+                    ParseErrorKind::InvalidCharacter,
+                    line_number,
+                    line,
+                    label,
+                ));
+            }
+            if labels.insert(label, statements.len()).is_some() {
+                return Err(ParseError::in_line(
+                    ParseErrorKind::DuplicateLabel,
+                    line_number,
+                    line,
+                    label,
+                ));
+            }
+            code = &code[colon + 1..];
+        }
+
+        let mut tokens = code.split_whitespace();
+        let op = match tokens.next() {
+            Some(op) => op,
+            None => continue,
+        };
+        let arg = tokens.next();
+        if let Some(extra) = tokens.next() {
+            return Err(ParseError::in_line(
+                ParseErrorKind::InvalidCharacter,
+                line_number,
+                line,
+                extra,
+            ));
+        }
+        statements.push(Statement {
+            line_number,
+            line,
+            op,
+            arg,
+        });
+    }
+
+    statements
+        .iter()
+        .enumerate()
+        .map(|(index, statement)| statement.assemble(index, &labels))
+        .collect()
+}
+
+/// Prints a program in the puzzle input format.
+pub fn disassemble(program: &[Instruction]) -> String {
+    program
+        .iter()
+        .map(|instruction| instruction.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assemble() {
+        let text = "\
+; count down from three
+        acc +3
+loop:   jz end      ; done?
+        acc -1
+        jmp loop
+end:";
+        let program = assemble(text).unwrap();
+        assert_eq!(
+            program,
+            [
+                Instruction::Accumulate(3),
+                Instruction::JumpIfZero(3),
+                Instruction::Accumulate(-1),
+                Instruction::Jump(-2),
+            ]
+        );
+        assert_eq!(disassemble(&program), "acc +3\njz +3\nacc -1\njmp -2");
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let text = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+        let program = Instruction::parse(text).unwrap();
+        assert_eq!(disassemble(&program), text);
+        assert_eq!(assemble(text).unwrap(), program);
+    }
+
+    #[test]
+    fn test_assemble_invalid() {
+        assert_eq!(
+            assemble("a: nop +0\na: nop +0"),
+            Err(ParseError::new(ParseErrorKind::DuplicateLabel, 2, 1, "a"))
+        );
+        assert_eq!(
+            assemble("jmp nowhere"),
+            Err(ParseError::new(
+                ParseErrorKind::UnknownLabel,
+                1,
+                5,
+                "nowhere"
+            ))
+        );
+        assert_eq!(
+            assemble("acc start"),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidNumber,
+                1,
+                5,
+                "start"
+            ))
+        );
+        assert_eq!(
+            assemble("  jmp ; comment"),
+            Err(ParseError::new(ParseErrorKind::UnexpectedEnd, 1, 6, ""))
+        );
+        assert_eq!(
+            assemble("div +2"),
+            Err(ParseError::new(
+                ParseErrorKind::UnknownInstruction,
+                1,
+                1,
+                "div"
+            ))
+        );
+        assert_eq!(
+            assemble("nop +0 +1"),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidCharacter,
+                1,
+                8,
+                "+1"
+            ))
+        );
+        assert_eq!(
+            assemble("1x: nop +0"),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidCharacter,
+                1,
+                1,
+                "1x"
+            ))
+        );
+    }
+}
@@ -0,0 +1,444 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::{flip, leads_to_end, terminating_instructions, Instruction};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EdgeKind {
+    FallThrough,
+    Jump,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Target {
+    Block(usize),
+    /// The instruction directly after the last one, where the program terminates.
+    End,
+    OutOfBounds(isize),
+}
+
+/// A run of instructions that is only entered at `start` and only left after `end - 1`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub reachable: bool,
+    pub in_loop: bool,
+    pub reaches_end: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: Target,
+    pub kind: EdgeKind,
+    pub in_loop: bool,
+}
+
+impl Edge {
+    pub fn is_terminating(&self) -> bool {
+        self.to == Target::End
+    }
+}
+
+/// The control-flow graph of a program over basic blocks. The analysis is static, so both
+/// sides of a conditional jump are followed.
+pub struct ControlFlowGraph<'a> {
+    program: &'a [Instruction],
+    pub blocks: Vec<Block>,
+    pub edges: Vec<Edge>,
+}
+
+/// Marks everything reachable from `start` by following `next`.
+fn search(start: &[usize], next: &[Vec<usize>]) -> Vec<bool> {
+    let mut found = vec![false; next.len()];
+    let mut stack = start.to_vec();
+    while let Some(index) = stack.pop() {
+        if !found[index] {
+            found[index] = true;
+            stack.extend(&next[index]);
+        }
+    }
+    found
+}
+
+/// Numbers the strongly connected components of the graph with Tarjan's algorithm, returning
+/// the component of every node. Iterative, so long programs don't overflow the stack.
+fn strongly_connected_components(next: &[Vec<usize>]) -> Vec<usize> {
+    const UNVISITED: usize = usize::MAX;
+    let mut order = vec![UNVISITED; next.len()];
+    let mut low = vec![0; next.len()];
+    let mut on_stack = vec![false; next.len()];
+    let mut stack = Vec::new();
+    let mut component = vec![0; next.len()];
+    let mut visited = 0;
+    let mut components = 0;
+
+    for root in 0..next.len() {
+        if order[root] != UNVISITED {
+            continue;
+        }
+        let mut calls = vec![(root, 0)];
+        order[root] = visited;
+        low[root] = visited;
+        visited += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&(node, child)) = calls.last() {
+            if let Some(&to) = next[node].get(child) {
+                calls.last_mut().unwrap().1 += 1;
+                if order[to] == UNVISITED {
+                    order[to] = visited;
+                    low[to] = visited;
+                    visited += 1;
+                    stack.push(to);
+                    on_stack[to] = true;
+                    calls.push((to, 0));
+                } else if on_stack[to] {
+                    low[node] = low[node].min(order[to]);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            if low[node] == order[node] {
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component[member] = components;
+                    if member == node {
+                        break;
+                    }
+                }
+                components += 1;
+            }
+        }
+    }
+    component
+}
+
+impl<'a> ControlFlowGraph<'a> {
+    pub fn new(program: &'a [Instruction]) -> ControlFlowGraph<'a> {
+        let len = program.len();
+        let mut leaders = vec![false; len + 1];
+        leaders[0] = true;
+        leaders[len] = true;
+        for (index, instruction) in program.iter().enumerate() {
+            let successors = instruction.successors(index);
+            if successors != [index as isize + 1] {
+                leaders[index + 1] = true;
+                for target in successors
+                    .into_iter()
+                    .filter(|t| (0..len as isize).contains(t))
+                {
+                    leaders[target as usize] = true;
+                }
+            }
+        }
+        let starts: Vec<usize> = (0..=len).filter(|&index| leaders[index]).collect();
+        let ranges: Vec<(usize, usize)> = starts.windows(2).map(|w| (w[0], w[1])).collect();
+        let block_of = |index: usize| starts.partition_point(|&start| start <= index) - 1;
+
+        let mut edges = Vec::new();
+        for (block, &(_, end)) in ranges.iter().enumerate() {
+            let last = end - 1;
+            for target in program[last].successors(last) {
+                let kind = match program[last] {
+                    Instruction::Jump(_) => EdgeKind::Jump,
+                    _ if target == end as isize => EdgeKind::FallThrough,
+                    _ => EdgeKind::Jump,
+                };
+                let to = if target == len as isize {
+                    Target::End
+                } else if (0..len as isize).contains(&target) {
+                    Target::Block(block_of(target as usize))
+                } else {
+                    Target::OutOfBounds(target)
+                };
+                edges.push(Edge {
+                    from: block,
+                    to,
+                    kind,
+                    in_loop: false,
+                });
+            }
+        }
+
+        let mut successors = vec![Vec::new(); ranges.len()];
+        let mut predecessors = vec![Vec::new(); ranges.len()];
+        let mut exits = Vec::new();
+        for edge in &edges {
+            match edge.to {
+                Target::Block(to) => {
+                    successors[edge.from].push(to);
+                    predecessors[to].push(edge.from);
+                }
+                Target::End => exits.push(edge.from),
+                Target::OutOfBounds(_) => (),
+            }
+        }
+
+        let entry: &[usize] = if ranges.is_empty() { &[] } else { &[0] };
+        let reachable = search(entry, &successors);
+        let reaches_end = search(&exits, &predecessors);
+        // An edge is part of a loop when its destination leads back to its source, which is
+        // when both are in the same strongly connected component
+        let component = strongly_connected_components(&successors);
+        let mut component_size = vec![0; ranges.len()];
+        for &c in &component {
+            component_size[c] += 1;
+        }
+        for edge in edges.iter_mut() {
+            if let Target::Block(to) = edge.to {
+                edge.in_loop = component[to] == component[edge.from];
+            }
+        }
+
+        let blocks = ranges
+            .iter()
+            .enumerate()
+            .map(|(block, &(start, end))| Block {
+                start,
+                end,
+                reachable: reachable[block],
+                in_loop: component_size[component[block]] > 1 || successors[block].contains(&block),
+                reaches_end: reaches_end[block],
+            })
+            .collect();
+
+        ControlFlowGraph {
+            program,
+            blocks,
+            edges,
+        }
+    }
+
+    /// The reachable `jmp`/`nop` instructions that, once flipped, lead into code from which the
+    /// end can be reached. These are the only flips that can repair the program.
+    pub fn useful_flips(&self) -> Vec<usize> {
+        let terminates = terminating_instructions(self.program);
+        self.blocks
+            .iter()
+            .filter(|block| block.reachable)
+            .flat_map(|block| block.start..block.end)
+            .filter(|&index| {
+                flip(&self.program[index])
+                    .is_some_and(|instruction| leads_to_end(&terminates, &instruction, index))
+            })
+            .collect()
+    }
+
+    /// Exports the graph in Graphviz DOT format. Unreachable blocks are grey and dashed, loops
+    /// are red and the terminating edges are green.
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph program {\n    node [shape=box, fontname=\"monospace\"];\n");
+        for block in &self.blocks {
+            let label: String = (block.start..block.end)
+                .map(|index| format!("{}: {}\\l", index, self.program[index]))
+                .collect();
+            let style = if !block.reachable {
+                ", style=dashed, color=gray, fontcolor=gray"
+            } else if block.in_loop {
+                ", color=red"
+            } else {
+                ""
+            };
+            dot += &format!("    b{} [label=\"{}\"{}];\n", block.start, label, style);
+        }
+        if self.edges.iter().any(Edge::is_terminating) {
+            dot += "    end [shape=doublecircle];\n";
+        }
+        for (index, edge) in self.edges.iter().enumerate() {
+            if let Target::OutOfBounds(target) = edge.to {
+                dot += &format!(
+                    "    out{} [label=\"{}\", shape=octagon, color=orange];\n",
+                    index, target
+                );
+            }
+        }
+
+        for (index, edge) in self.edges.iter().enumerate() {
+            let to = match edge.to {
+                Target::Block(block) => format!("b{}", self.blocks[block].start),
+                Target::End => String::from("end"),
+                Target::OutOfBounds(_) => format!("out{}", index),
+            };
+            let mut attributes = Vec::new();
+            if edge.kind == EdgeKind::FallThrough {
+                attributes.push("style=dotted");
+            }
+            if edge.is_terminating() {
+                attributes.push("color=green, penwidth=2");
+            } else if edge.in_loop {
+                attributes.push("color=red");
+            }
+            let attributes = if attributes.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", attributes.join(", "))
+            };
+            dot += &format!(
+                "    b{} -> {}{};\n",
+                self.blocks[edge.from].start, to, attributes
+            );
+        }
+        dot += "}\n";
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks() {
+        let text = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+        let program = Instruction::parse(text).unwrap();
+        let graph = ControlFlowGraph::new(&program);
+
+        let starts: Vec<usize> = graph.blocks.iter().map(|block| block.start).collect();
+        assert_eq!(starts, [0, 1, 3, 5, 6, 8]);
+        let reachable: Vec<bool> = graph.blocks.iter().map(|block| block.reachable).collect();
+        assert_eq!(reachable, [true, true, true, false, true, false]);
+        let in_loop: Vec<bool> = graph.blocks.iter().map(|block| block.in_loop).collect();
+        assert_eq!(in_loop, [false, true, true, false, true, false]);
+        let reaches_end: Vec<bool> = graph.blocks.iter().map(|b| b.reaches_end).collect();
+        assert_eq!(reaches_end, [false, false, false, false, false, true]);
+
+        assert_eq!(
+            graph.edges,
+            [
+                Edge {
+                    from: 0,
+                    to: Target::Block(1),
+                    kind: EdgeKind::FallThrough,
+                    in_loop: false
+                },
+                Edge {
+                    from: 1,
+                    to: Target::Block(4),
+                    kind: EdgeKind::Jump,
+                    in_loop: true
+                },
+                Edge {
+                    from: 2,
+                    to: Target::Block(1),
+                    kind: EdgeKind::Jump,
+                    in_loop: true
+                },
+                Edge {
+                    from: 3,
+                    to: Target::Block(4),
+                    kind: EdgeKind::FallThrough,
+                    in_loop: false
+                },
+                Edge {
+                    from: 4,
+                    to: Target::Block(2),
+                    kind: EdgeKind::Jump,
+                    in_loop: true
+                },
+                Edge {
+                    from: 5,
+                    to: Target::End,
+                    kind: EdgeKind::FallThrough,
+                    in_loop: false
+                },
+            ]
+        );
+        assert_eq!(graph.useful_flips(), [7]);
+    }
+
+    #[test]
+    fn test_conditional_jumps() {
+        let program = Instruction::parse("acc +2\njz +3\nacc -1\njmp -2\njmp -9").unwrap();
+        let graph = ControlFlowGraph::new(&program);
+
+        let edges: Vec<(usize, Target, EdgeKind)> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from, edge.to, edge.kind))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                (0, Target::Block(1), EdgeKind::FallThrough),
+                (1, Target::Block(2), EdgeKind::FallThrough),
+                (1, Target::Block(3), EdgeKind::Jump),
+                (2, Target::Block(1), EdgeKind::Jump),
+                (3, Target::OutOfBounds(-5), EdgeKind::Jump),
+            ]
+        );
+        let in_loop: Vec<bool> = graph.blocks.iter().map(|block| block.in_loop).collect();
+        assert_eq!(in_loop, [false, true, true, false]);
+        assert!(graph.blocks.iter().all(|block| !block.reaches_end));
+        assert_eq!(graph.useful_flips(), [4]);
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // 0 -> 1 -> 2 -> 0 and 2 -> 3 -> 4 -> 3, 5 on its own
+        let next = vec![vec![1], vec![2], vec![0, 3], vec![4], vec![3], vec![]];
+        let component = strongly_connected_components(&next);
+        assert_eq!(component[0], component[1]);
+        assert_eq!(component[1], component[2]);
+        assert_eq!(component[3], component[4]);
+        assert_ne!(component[0], component[3]);
+        assert_ne!(component[5], component[0]);
+        assert_ne!(component[5], component[3]);
+
+        let long: Vec<Vec<usize>> = (0..100_000)
+            .map(|node| vec![(node + 1) % 100_000])
+            .collect();
+        assert!(strongly_connected_components(&long).iter().all(|&c| c == 0));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let program = Instruction::parse("nop +2\njmp +0\nacc +1\njmp -4").unwrap();
+        let graph = ControlFlowGraph::new(&program);
+        assert_eq!(
+            graph.to_dot(),
+            "\
+digraph program {
+    node [shape=box, fontname=\"monospace\"];
+    b0 [label=\"0: nop +2\\l\"];
+    b1 [label=\"1: jmp +0\\l\", color=red];
+    b2 [label=\"2: acc +1\\l3: jmp -4\\l\", style=dashed, color=gray, fontcolor=gray];
+    out2 [label=\"-1\", shape=octagon, color=orange];
+    b0 -> b1 [style=dotted];
+    b1 -> b1 [color=red];
+    b2 -> out2;
+}
+"
+        );
+    }
+}
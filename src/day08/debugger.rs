@@ -0,0 +1,410 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::{Instruction, Machine, Termination};
+use std::collections::BTreeSet;
+
+pub const HELP: &str = "\
+Commands:
+    s, step [n]       execute n instructions (default 1)
+    c, continue       run until a breakpoint, watchpoint or the end of the program
+    rs, back [n]      undo n instructions (default 1)
+    b, break <pc>     stop before executing the instruction at pc
+    delete <pc>       remove the breakpoint at pc
+    watch [value]     stop when the accumulator changes, or changes to value
+    unwatch           remove the watchpoint
+    l, list [n]       show n instructions around pc (default 3)
+    info              show pc, accumulator, breakpoints and watchpoint
+    h, help           show this help
+    q, quit           leave the debugger
+An empty line repeats the previous command.";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Watchpoint {
+    Change,
+    Value(i32),
+}
+
+pub struct Debugger<'a> {
+    machine: Machine<'a>,
+    breakpoints: BTreeSet<usize>,
+    watchpoint: Option<Watchpoint>,
+}
+
+fn parse_number<T: std::str::FromStr>(arg: Option<&str>, default: Option<T>) -> Result<T, String> {
+    match arg {
+        Some(arg) => arg.parse().map_err(|_| format!("Invalid number '{}'", arg)),
+        None => default.ok_or_else(|| String::from("Missing number")),
+    }
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: &'a [Instruction]) -> Debugger<'a> {
+        Debugger {
+            machine: Machine::new(program),
+            breakpoints: BTreeSet::new(),
+            watchpoint: None,
+        }
+    }
+
+    pub fn machine(&self) -> &Machine<'a> {
+        &self.machine
+    }
+
+    /// Runs a single command line and returns what to show the user.
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or("");
+        let arg = words.next();
+        match command {
+            "s" | "step" => {
+                for _ in 0..parse_number(arg, Some(1))? {
+                    if self.machine.step().is_some() {
+                        break;
+                    }
+                }
+                Ok(self.status(None))
+            }
+            "c" | "continue" => {
+                let reason = self.run();
+                Ok(self.status(reason))
+            }
+            "rs" | "back" => {
+                let count = parse_number(arg, Some(1))?;
+                let undone = (0..count).take_while(|_| self.machine.back()).count();
+                if undone < count {
+                    Ok(format!(
+                        "At the start of the program\n{}",
+                        self.status(None)
+                    ))
+                } else {
+                    Ok(self.status(None))
+                }
+            }
+            "b" | "break" => {
+                let pc = parse_number(arg, None)?;
+                if pc >= self.machine.program().len() {
+                    return Err(format!("No instruction at {}", pc));
+                }
+                self.breakpoints.insert(pc);
+                Ok(format!("Breakpoint at {}", pc))
+            }
+            "delete" => {
+                let pc = parse_number(arg, None)?;
+                if self.breakpoints.remove(&pc) {
+                    Ok(format!("Deleted breakpoint at {}", pc))
+                } else {
+                    Err(format!("No breakpoint at {}", pc))
+                }
+            }
+            "watch" => {
+                self.watchpoint = Some(match arg {
+                    Some(_) => Watchpoint::Value(parse_number(arg, None)?),
+                    None => Watchpoint::Change,
+                });
+                Ok(self.describe_watchpoint())
+            }
+            "unwatch" => {
+                self.watchpoint = None;
+                Ok(self.describe_watchpoint())
+            }
+            "l" | "list" => Ok(self.list(parse_number(arg, Some(3))?)),
+            "info" => Ok(format!(
+                "pc {}, acc {}, {} instructions executed\nBreakpoints: {}\n{}",
+                self.machine.pc(),
+                self.machine.acc(),
+                self.machine.trace().len(),
+                if self.breakpoints.is_empty() {
+                    String::from("none")
+                } else {
+                    self.breakpoints
+                        .iter()
+                        .map(|pc| pc.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+                self.describe_watchpoint()
+            )),
+            "h" | "help" => Ok(String::from(HELP)),
+            _ => Err(format!("Unknown command '{}', try 'help'", command)),
+        }
+    }
+
+    /// Continues until the program stops or a breakpoint or watchpoint is hit, and returns why.
+    fn run(&mut self) -> Option<String> {
+        let breakpoints = &self.breakpoints;
+        let watchpoint = self.watchpoint;
+        let mut last_acc = self.machine.acc();
+        let mut reason = None;
+        self.machine.run_until(|machine| {
+            let acc = machine.acc();
+            let changed = acc != last_acc;
+            let watched = match watchpoint {
+                Some(Watchpoint::Change) => changed,
+                Some(Watchpoint::Value(value)) => changed && acc == value,
+                None => false,
+            };
+            let mut reasons = Vec::new();
+            if watched {
+                reasons.push(format!("Accumulator changed from {} to {}", last_acc, acc));
+            }
+            if breakpoints.contains(&machine.pc()) {
+                reasons.push(format!("Breakpoint at {}", machine.pc()));
+            }
+            if !reasons.is_empty() {
+                reason = Some(reasons.join("\n"));
+            }
+            last_acc = acc;
+            reason.is_some()
+        });
+        reason
+    }
+
+    fn describe_watchpoint(&self) -> String {
+        match self.watchpoint {
+            Some(Watchpoint::Change) => String::from("Watching any accumulator change"),
+            Some(Watchpoint::Value(value)) => format!("Watching the accumulator become {}", value),
+            None => String::from("No watchpoint"),
+        }
+    }
+
+    fn status(&self, reason: Option<String>) -> String {
+        let machine = &self.machine;
+        let location = match machine.termination() {
+            Some(Termination::Terminated) => {
+                format!("Program terminated with acc {}", machine.acc())
+            }
+            Some(Termination::InfiniteLoop { pc }) => format!(
+                "Infinite loop: instruction {} would run twice, acc {}",
+                pc,
+                machine.acc()
+            ),
+            Some(Termination::JumpOutOfBounds { pc, target }) => format!(
+                "Jump out of bounds at {} to {}, acc {}",
+                pc,
+                target,
+                machine.acc()
+            ),
+            Some(Termination::Overflow { pc }) => {
+                format!("Accumulator overflow at {}, acc {}", pc, machine.acc())
+            }
+            None => format!(
+                "{:>4}  {}    acc {}",
+                machine.pc(),
+                machine.program()[machine.pc()],
+                machine.acc()
+            ),
+        };
+        match reason {
+            Some(reason) => format!("{}\n{}", reason, location),
+            None => location,
+        }
+    }
+
+    fn list(&self, radius: usize) -> String {
+        let program = self.machine.program();
+        let pc = self.machine.pc();
+        let start = pc.saturating_sub(radius);
+        let end = (pc + radius + 1).min(program.len());
+        (start..end)
+            .map(|index| {
+                format!(
+                    "{}{}{:>4}  {}",
+                    if index == pc { "=>" } else { "  " },
+                    if self.breakpoints.contains(&index) {
+                        "*"
+                    } else {
+                        " "
+                    },
+                    index,
+                    program[index]
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    #[test]
+    fn test_step_and_back() {
+        let program = Instruction::parse(EXAMPLE).unwrap();
+        let mut debugger = Debugger::new(&program);
+
+        assert_eq!(
+            debugger.execute("step"),
+            Ok(String::from("   1  acc +1    acc 0"))
+        );
+        assert_eq!(
+            debugger.execute("s 2"),
+            Ok(String::from("   6  acc +1    acc 1"))
+        );
+        assert_eq!(
+            debugger.execute("back"),
+            Ok(String::from("   2  jmp +4    acc 1"))
+        );
+        assert_eq!(
+            debugger.execute("back 5"),
+            Ok(String::from(
+                "At the start of the program\n   0  nop +0    acc 0"
+            ))
+        );
+        assert_eq!(
+            debugger.execute("step 100"),
+            Ok(String::from(
+                "Infinite loop: instruction 1 would run twice, acc 5"
+            ))
+        );
+        assert_eq!(
+            debugger.execute("rs"),
+            Ok(String::from("   4  jmp -3    acc 5"))
+        );
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let program = Instruction::parse(EXAMPLE).unwrap();
+        let mut debugger = Debugger::new(&program);
+
+        assert_eq!(
+            debugger.execute("break 7"),
+            Ok(String::from("Breakpoint at 7"))
+        );
+        assert_eq!(
+            debugger.execute("continue"),
+            Ok(String::from("Breakpoint at 7\n   7  jmp -4    acc 2"))
+        );
+        assert_eq!(
+            debugger.execute("list 2"),
+            Ok(String::from(
+                "      5  acc -99\n      6  acc +1\n=>*   7  jmp -4\n      8  acc +6"
+            ))
+        );
+        assert_eq!(
+            debugger.execute("delete 7"),
+            Ok(String::from("Deleted breakpoint at 7"))
+        );
+        assert_eq!(
+            debugger.execute("c"),
+            Ok(String::from(
+                "Infinite loop: instruction 1 would run twice, acc 5"
+            ))
+        );
+        assert_eq!(
+            debugger.execute("info"),
+            Ok(String::from(
+                "pc 1, acc 5, 7 instructions executed\nBreakpoints: none\nNo watchpoint"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        let program = Instruction::parse(EXAMPLE).unwrap();
+        let mut debugger = Debugger::new(&program);
+
+        debugger.execute("watch").unwrap();
+        assert_eq!(
+            debugger.execute("continue"),
+            Ok(String::from(
+                "Accumulator changed from 0 to 1\n   2  jmp +4    acc 1"
+            ))
+        );
+        debugger.execute("watch 5").unwrap();
+        assert_eq!(
+            debugger.execute("continue"),
+            Ok(String::from(
+                "Accumulator changed from 2 to 5\n   4  jmp -3    acc 5"
+            ))
+        );
+        assert_eq!(
+            debugger.execute("unwatch"),
+            Ok(String::from("No watchpoint"))
+        );
+    }
+
+    #[test]
+    fn test_watchpoint_and_breakpoint() {
+        let program = Instruction::parse(EXAMPLE).unwrap();
+        let mut debugger = Debugger::new(&program);
+
+        debugger.execute("watch").unwrap();
+        assert_eq!(debugger.execute("b 2"), Ok(String::from("Breakpoint at 2")));
+        assert_eq!(
+            debugger.execute("c"),
+            Ok(String::from(
+                "Accumulator changed from 0 to 1\nBreakpoint at 2\n   2  jmp +4    acc 1"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_commands() {
+        let program = Instruction::parse("nop +0\njmp -1").unwrap();
+        let mut debugger = Debugger::new(&program);
+
+        assert_eq!(
+            debugger.execute("jump 1"),
+            Err(String::from("Unknown command 'jump', try 'help'"))
+        );
+        assert_eq!(
+            debugger.execute("step x"),
+            Err(String::from("Invalid number 'x'"))
+        );
+        assert_eq!(
+            debugger.execute("break"),
+            Err(String::from("Missing number"))
+        );
+        assert_eq!(
+            debugger.execute("break 2"),
+            Err(String::from("No instruction at 2"))
+        );
+        assert_eq!(
+            debugger.execute("delete 0"),
+            Err(String::from("No breakpoint at 0"))
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let program = Instruction::parse("acc +70000\nmul +70000").unwrap();
+        let mut debugger = Debugger::new(&program);
+
+        assert_eq!(
+            debugger.execute("continue"),
+            Ok(String::from("Accumulator overflow at 1, acc 70000"))
+        );
+        assert_eq!(
+            debugger.execute("back"),
+            Ok(String::from("   1  mul +70000    acc 70000"))
+        );
+    }
+}
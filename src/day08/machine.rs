@@ -0,0 +1,317 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::Instruction;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Termination {
+    /// The instruction directly after the last one was about to be executed.
+    Terminated,
+    /// The instruction at `pc` was about to be executed for a second time.
+    InfiniteLoop { pc: usize },
+    /// The jump at `pc` targets an instruction outside the program.
+    JumpOutOfBounds { pc: usize, target: isize },
+    /// The instruction at `pc` would take the accumulator outside of `i32`.
+    Overflow { pc: usize },
+}
+
+/// The state of the machine just before the instruction at `pc` was executed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TraceEntry {
+    pub pc: usize,
+    pub acc: i32,
+}
+
+pub struct Machine<'a> {
+    program: &'a [Instruction],
+    pc: usize,
+    acc: i32,
+    visited: Vec<bool>,
+    trace: Vec<TraceEntry>,
+    termination: Option<Termination>,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(program: &'a [Instruction]) -> Machine<'a> {
+        Machine {
+            program,
+            pc: 0,
+            acc: 0,
+            visited: vec![false; program.len()],
+            trace: Vec::new(),
+            termination: if program.is_empty() {
+                Some(Termination::Terminated)
+            } else {
+                None
+            },
+        }
+    }
+
+    pub fn program(&self) -> &'a [Instruction] {
+        self.program
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn acc(&self) -> i32 {
+        self.acc
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        &self.trace
+    }
+
+    pub fn termination(&self) -> Option<Termination> {
+        self.termination
+    }
+
+    /// Executes a single instruction. Returns the reason the machine stopped once it can't
+    /// continue; stepping a stopped machine does nothing.
+    pub fn step(&mut self) -> Option<Termination> {
+        if self.termination.is_some() {
+            return self.termination;
+        }
+
+        self.trace.push(TraceEntry {
+            pc: self.pc,
+            acc: self.acc,
+        });
+        self.visited[self.pc] = true;
+        let acc = match self.program[self.pc] {
+            Instruction::Accumulate(arg) => self.acc.checked_add(arg),
+            Instruction::Multiply(arg) => self.acc.checked_mul(arg),
+            _ => Some(self.acc),
+        };
+        match acc {
+            Some(acc) => self.acc = acc,
+            None => {
+                self.termination = Some(Termination::Overflow { pc: self.pc });
+                return self.termination;
+            }
+        }
+
+        let target = match self.program[self.pc] {
+            Instruction::Jump(arg) => self.pc as isize + arg as isize,
+            Instruction::Accumulate(_) | Instruction::NoOperation(_) | Instruction::Multiply(_) => {
+                self.pc as isize + 1
+            }
+            Instruction::JumpIfZero(arg) if self.acc == 0 => self.pc as isize + arg as isize,
+            Instruction::JumpIfNotZero(arg) if self.acc != 0 => self.pc as isize + arg as isize,
+            Instruction::JumpIfZero(_) | Instruction::JumpIfNotZero(_) => self.pc as isize + 1,
+        };
+
+        let len = self.program.len() as isize;
+        if target < 0 || target > len {
+            self.termination = Some(Termination::JumpOutOfBounds {
+                pc: self.pc,
+                target,
+            });
+        } else if target == len {
+            self.pc = target as usize;
+            self.termination = Some(Termination::Terminated);
+        } else {
+            self.pc = target as usize;
+            if self.visited[self.pc] {
+                self.termination = Some(Termination::InfiniteLoop { pc: self.pc });
+            }
+        }
+        self.termination
+    }
+
+    /// Steps until the machine stops or `predicate` holds after a step. Returns `None` when
+    /// stopped by the predicate.
+    pub fn run_until<F>(&mut self, mut predicate: F) -> Option<Termination>
+    where
+        F: FnMut(&Machine) -> bool,
+    {
+        loop {
+            if let Some(termination) = self.step() {
+                return Some(termination);
+            }
+            if predicate(self) {
+                return None;
+            }
+        }
+    }
+
+    /// Undoes the last executed instruction using the trace. Returns `false` at the start.
+    pub fn back(&mut self) -> bool {
+        match self.trace.pop() {
+            Some(entry) => {
+                self.pc = entry.pc;
+                self.acc = entry.acc;
+                self.visited[entry.pc] = false;
+                self.termination = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn run(&mut self) -> Termination {
+        self.run_until(|_| false).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    #[test]
+    fn test_step() {
+        let program = Instruction::parse(EXAMPLE).unwrap();
+        let mut machine = Machine::new(&program);
+
+        assert_eq!(machine.step(), None);
+        assert_eq!(machine.step(), None);
+        assert_eq!((machine.pc(), machine.acc()), (2, 1));
+        assert_eq!(machine.step(), None);
+        assert_eq!(machine.pc(), 6);
+        assert_eq!(
+            machine.trace(),
+            [
+                TraceEntry { pc: 0, acc: 0 },
+                TraceEntry { pc: 1, acc: 0 },
+                TraceEntry { pc: 2, acc: 1 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_infinite_loop() {
+        let program = Instruction::parse(EXAMPLE).unwrap();
+        let mut machine = Machine::new(&program);
+
+        assert_eq!(machine.run(), Termination::InfiniteLoop { pc: 1 });
+        assert_eq!(machine.acc(), 5);
+        assert_eq!(machine.trace().len(), 7);
+        assert_eq!(machine.step(), Some(Termination::InfiniteLoop { pc: 1 }));
+        assert_eq!(machine.trace().len(), 7);
+    }
+
+    #[test]
+    fn test_terminated() {
+        let program = Instruction::parse(&EXAMPLE.replace("jmp -4", "nop -4")).unwrap();
+        let mut machine = Machine::new(&program);
+
+        assert_eq!(machine.run(), Termination::Terminated);
+        assert_eq!((machine.pc(), machine.acc()), (9, 8));
+
+        assert_eq!(Machine::new(&[]).run(), Termination::Terminated);
+    }
+
+    #[test]
+    fn test_jump_out_of_bounds() {
+        let program = Instruction::parse("acc +1\njmp -2").unwrap();
+        let mut machine = Machine::new(&program);
+        assert_eq!(
+            machine.run(),
+            Termination::JumpOutOfBounds { pc: 1, target: -1 }
+        );
+        assert_eq!((machine.pc(), machine.acc()), (1, 1));
+
+        let program = Instruction::parse("jmp +3\nnop +0").unwrap();
+        assert_eq!(
+            Machine::new(&program).run(),
+            Termination::JumpOutOfBounds { pc: 0, target: 3 }
+        );
+    }
+
+    #[test]
+    fn test_run_until() {
+        let program = Instruction::parse(EXAMPLE).unwrap();
+        let mut machine = Machine::new(&program);
+
+        assert_eq!(machine.run_until(|machine| machine.acc() >= 4), None);
+        assert_eq!((machine.pc(), machine.acc()), (4, 5));
+        assert_eq!(
+            machine.run_until(|machine| machine.pc() == 5),
+            Some(Termination::InfiniteLoop { pc: 1 })
+        );
+    }
+
+    #[test]
+    fn test_extended_instructions() {
+        let program = Instruction::parse("acc +3\nmul -2\njnz +2\nacc +100\njz -4").unwrap();
+        let mut machine = Machine::new(&program);
+        assert_eq!(machine.run(), Termination::Terminated);
+        assert_eq!(machine.acc(), -6);
+        let pcs: Vec<usize> = machine.trace().iter().map(|entry| entry.pc).collect();
+        assert_eq!(pcs, [0, 1, 2, 4]);
+
+        let program = Instruction::parse("jz +2\nacc +1\nacc +0\njz -3").unwrap();
+        assert_eq!(
+            Machine::new(&program).run(),
+            Termination::InfiniteLoop { pc: 0 }
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let program = Instruction::parse("acc +70000\nmul +70000\nacc +1").unwrap();
+        let mut machine = Machine::new(&program);
+        assert_eq!(machine.run(), Termination::Overflow { pc: 1 });
+        assert_eq!((machine.pc(), machine.acc()), (1, 70000));
+        assert_eq!(machine.step(), Some(Termination::Overflow { pc: 1 }));
+
+        assert!(machine.back());
+        assert_eq!(
+            (machine.pc(), machine.acc(), machine.termination()),
+            (1, 70000, None)
+        );
+
+        let program = Instruction::parse("acc +2147483647\nacc +1").unwrap();
+        let mut machine = Machine::new(&program);
+        assert_eq!(machine.run(), Termination::Overflow { pc: 1 });
+        assert_eq!(machine.acc(), i32::MAX);
+    }
+
+    #[test]
+    fn test_back() {
+        let program = Instruction::parse(EXAMPLE).unwrap();
+        let mut machine = Machine::new(&program);
+        assert!(!machine.back());
+
+        machine.run();
+        assert!(machine.back());
+        assert_eq!(
+            (machine.pc(), machine.acc(), machine.termination()),
+            (4, 5, None)
+        );
+        for _ in 0..6 {
+            assert!(machine.back());
+        }
+        assert_eq!((machine.pc(), machine.acc()), (0, 0));
+        assert!(!machine.back());
+
+        assert_eq!(machine.run(), Termination::InfiniteLoop { pc: 1 });
+        assert_eq!(machine.acc(), 5);
+    }
+}
@@ -15,63 +15,258 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::collections::BTreeMap;
-use std::ops::Index;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use std::fmt;
+
+mod asm;
+pub mod cfg;
+pub mod debugger;
+mod machine;
+
+pub use asm::{assemble, disassemble};
+pub use machine::{Machine, Termination, TraceEntry};
 
 #[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
 pub enum Instruction {
     Accumulate(i32),
     Jump(i32),
     NoOperation(i32),
+    Multiply(i32),
+    JumpIfZero(i32),
+    JumpIfNotZero(i32),
+}
+
+/// An entry in the instruction set. Adding an instruction takes a variant of [`Instruction`], an
+/// entry in [`OPCODES`] and its behaviour in [`Machine::step`].
+pub struct Opcode {
+    pub mnemonic: &'static str,
+    /// Whether the argument is a relative offset, which the assembler accepts as a label.
+    pub jump: bool,
+    pub build: fn(i32) -> Instruction,
+}
+
+pub const OPCODES: [Opcode; 6] = [
+    Opcode {
+        mnemonic: "acc",
+        jump: false,
+        build: Instruction::Accumulate,
+    },
+    Opcode {
+        mnemonic: "jmp",
+        jump: true,
+        build: Instruction::Jump,
+    },
+    Opcode {
+        mnemonic: "nop",
+        jump: false,
+        build: Instruction::NoOperation,
+    },
+    Opcode {
+        mnemonic: "mul",
+        jump: false,
+        build: Instruction::Multiply,
+    },
+    Opcode {
+        mnemonic: "jz",
+        jump: true,
+        build: Instruction::JumpIfZero,
+    },
+    Opcode {
+        mnemonic: "jnz",
+        jump: true,
+        build: Instruction::JumpIfNotZero,
+    },
+];
+
+impl Opcode {
+    pub fn find(mnemonic: &str) -> Option<&'static Opcode> {
+        OPCODES.iter().find(|opcode| opcode.mnemonic == mnemonic)
+    }
 }
 
 impl Instruction {
-    pub fn parse(text: &str) -> Vec<Instruction> {
+    pub fn parse(text: &str) -> Result<Vec<Instruction>, ParseError> {
         text.lines()
-            .map(|line| {
+            .enumerate()
+            .map(|(line_index, line)| {
+                let line_number = line_index + 1;
                 let mut split = line.split(' ');
                 let op = split.next().unwrap();
-                let arg: i32 = split.next().unwrap().parse().unwrap();
-                match op {
-                    "acc" => Instruction::Accumulate(arg),
-                    "jmp" => Instruction::Jump(arg),
-                    "nop" => Instruction::NoOperation(arg),
-                    _ => panic!(),
+                let arg = split.next().ok_or_else(|| {
+                    ParseError::new(
+                        ParseErrorKind::UnexpectedEnd,
+                        line_number,
+                        line.len() + 1,
+                        "",
+                    )
+                })?;
+                if let Some(extra) = split.next() {
+                    return Err(ParseError::in_line(
+                        ParseErrorKind::InvalidCharacter,
+                        line_number,
+                        line,
+                        extra,
+                    ));
                 }
+                let arg: i32 = arg.parse().map_err(|_| {
+                    ParseError::in_line(ParseErrorKind::InvalidNumber, line_number, line, arg)
+                })?;
+                let opcode = Opcode::find(op).ok_or_else(|| {
+                    ParseError::in_line(ParseErrorKind::UnknownInstruction, line_number, line, op)
+                })?;
+                Ok((opcode.build)(arg))
             })
             .collect()
     }
-}
 
-pub fn execute_once(program: &[Instruction]) -> (i32, bool) {
-    let mut program_counter = 0;
-    let mut accumulator = 0;
-    let mut instruction_executed = BTreeMap::new();
-    loop {
-        if *instruction_executed.get(&program_counter).unwrap_or(&false) {
-            return (accumulator, false);
-        } else {
-            instruction_executed.insert(program_counter, true);
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Instruction::Accumulate(_) => "acc",
+            Instruction::Jump(_) => "jmp",
+            Instruction::NoOperation(_) => "nop",
+            Instruction::Multiply(_) => "mul",
+            Instruction::JumpIfZero(_) => "jz",
+            Instruction::JumpIfNotZero(_) => "jnz",
         }
-        let instruction = program.index(program_counter);
-        match instruction {
-            Instruction::Accumulate(arg) => {
-                accumulator += arg;
-                program_counter += 1;
-            }
-            Instruction::Jump(arg) => {
-                program_counter = ((program_counter as isize) + (*arg as isize)) as usize
+    }
+
+    pub fn argument(&self) -> i32 {
+        match self {
+            Instruction::Accumulate(arg)
+            | Instruction::Jump(arg)
+            | Instruction::NoOperation(arg)
+            | Instruction::Multiply(arg)
+            | Instruction::JumpIfZero(arg)
+            | Instruction::JumpIfNotZero(arg) => *arg,
+        }
+    }
+
+    /// The instructions that may be executed after this one at `index`; conditional jumps have
+    /// two. Targets outside the program are included as is.
+    pub fn successors(&self, index: usize) -> Vec<isize> {
+        let next = index as isize + 1;
+        let target = index as isize + self.argument() as isize;
+        match self {
+            Instruction::Jump(_) => vec![target],
+            Instruction::JumpIfZero(_) | Instruction::JumpIfNotZero(_) if target != next => {
+                vec![next, target]
             }
-            Instruction::NoOperation(_) => {
-                program_counter += 1;
+            _ => vec![next],
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:+}", self.mnemonic(), self.argument())
+    }
+}
+
+/// Runs the program until it terminates or is about to repeat an instruction. Returns the
+/// accumulator and whether the program terminated normally.
+pub fn execute_once(program: &[Instruction]) -> (i32, bool) {
+    let mut machine = Machine::new(program);
+    let termination = machine.run();
+    (machine.acc(), termination == Termination::Terminated)
+}
+
+/// The single instruction change that makes a looping program terminate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Repair {
+    pub index: usize,
+    pub instruction: Instruction,
+    pub acc: i32,
+}
+
+fn flip(instruction: &Instruction) -> Option<Instruction> {
+    match instruction {
+        Instruction::Jump(arg) => Some(Instruction::NoOperation(*arg)),
+        Instruction::NoOperation(arg) => Some(Instruction::Jump(*arg)),
+        _ => None,
+    }
+}
+
+/// For every instruction, and the end of the program at index `len`, whether the end may be
+/// reached from it. Found by walking the jumps backwards from the end; both sides of a
+/// conditional jump are followed.
+pub(crate) fn terminating_instructions(program: &[Instruction]) -> Vec<bool> {
+    let len = program.len();
+    let mut predecessors = vec![Vec::new(); len + 1];
+    for (index, instruction) in program.iter().enumerate() {
+        for target in instruction.successors(index) {
+            if (0..=len as isize).contains(&target) {
+                predecessors[target as usize].push(index);
             }
         }
-        if program_counter >= program.len() {
-            return (accumulator, true);
+    }
+
+    let mut terminates = vec![false; len + 1];
+    let mut stack = vec![len];
+    while let Some(index) = stack.pop() {
+        if !terminates[index] {
+            terminates[index] = true;
+            stack.extend(&predecessors[index]);
         }
     }
+    terminates
+}
+
+/// Whether `instruction`, placed at `index`, continues into code marked by
+/// [`terminating_instructions`].
+pub(crate) fn leads_to_end(terminates: &[bool], instruction: &Instruction, index: usize) -> bool {
+    instruction
+        .successors(index)
+        .into_iter()
+        .any(|target| target >= 0 && terminates.get(target as usize) == Some(&true))
+}
+
+/// Finds the `jmp`/`nop` flip that makes the program terminate. The instructions from which the
+/// end may be reachable are found by walking the jumps backwards from the end; the fix is an
+/// executed instruction whose flipped successor is one of them. Returns `None` if the program
+/// already terminates or no single flip helps.
+///
+/// Without `jz`/`jnz` the reachability is exact, so the first candidate is the fix and this takes
+/// linear time. Conditional jumps make it an over-approximation: every candidate is then checked
+/// with a full run, which is quadratic in the worst case.
+pub fn repair(program: &[Instruction]) -> Option<Repair> {
+    let terminates = terminating_instructions(program);
+    let mut machine = Machine::new(program);
+    if machine.run() == Termination::Terminated {
+        return None;
+    }
+
+    let exact = !program.iter().any(|instruction| {
+        matches!(
+            instruction,
+            Instruction::JumpIfZero(_) | Instruction::JumpIfNotZero(_)
+        )
+    });
+    machine.trace().iter().find_map(|entry| {
+        let instruction = flip(&program[entry.pc])?;
+        if !leads_to_end(&terminates, &instruction, entry.pc) {
+            return None;
+        }
+
+        let mut repaired = program.to_vec();
+        repaired[entry.pc] = instruction.clone();
+        let mut machine = Machine::new(&repaired);
+        if machine.run() != Termination::Terminated {
+            debug_assert!(
+                !exact,
+                "the first candidate terminates without conditional jumps"
+            );
+            return None;
+        }
+        Some(Repair {
+            index: entry.pc,
+            instruction,
+            acc: machine.acc(),
+        })
+    })
 }
 
+/// Every program with a single `jmp`/`nop` flipped. Brute force reference for [`repair`].
 pub struct MutatedPrograms {
     count: usize,
     program: Vec<Instruction>,
@@ -92,7 +287,7 @@ impl Iterator for MutatedPrograms {
             return None;
         }
 
-        while let Instruction::Accumulate(_) = self.program[self.count] {
+        while flip(&self.program[self.count]).is_none() {
             self.count += 1;
             if self.count >= self.program.len() {
                 return None;
@@ -100,11 +295,7 @@ impl Iterator for MutatedPrograms {
         }
 
         let mut mutated_program = self.program.clone();
-        match mutated_program[self.count] {
-            Instruction::NoOperation(arg) => mutated_program[self.count] = Instruction::Jump(arg),
-            Instruction::Jump(arg) => mutated_program[self.count] = Instruction::NoOperation(arg),
-            Instruction::Accumulate(_) => panic!(),
-        }
+        mutated_program[self.count] = flip(&self.program[self.count]).unwrap();
 
         self.count += 1;
 
@@ -112,6 +303,28 @@ impl Iterator for MutatedPrograms {
     }
 }
 
+pub struct Day08;
+
+impl Solver for Day08 {
+    type Input = Vec<Instruction>;
+
+    fn parse(text: &str) -> Result<Vec<Instruction>, ParseError> {
+        Instruction::parse(text)
+    }
+
+    fn part1(program: &Vec<Instruction>) -> String {
+        let (acc, _completed) = execute_once(program);
+        acc.to_string()
+    }
+
+    fn part2(program: &Vec<Instruction>) -> String {
+        match repair(program) {
+            Some(repair) => repair.acc.to_string(),
+            None => String::from("No single jmp/nop flip makes the program terminate"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,7 +337,7 @@ nop +0
 acc +1
 jmp +4";
 
-        let instruction_list = Instruction::parse(text);
+        let instruction_list = Instruction::parse(text).unwrap();
 
         assert_eq!(instruction_list.len(), 3);
         assert_eq!(*instruction_list.index(0), Instruction::NoOperation(0));
@@ -132,6 +345,45 @@ jmp +4";
         assert_eq!(*instruction_list.index(2), Instruction::Jump(4));
     }
 
+    #[test]
+    fn test_parse_invalid() {
+        let text = "\
+nop +0
+div +1";
+        assert_eq!(
+            Instruction::parse(text),
+            Err(ParseError::new(
+                ParseErrorKind::UnknownInstruction,
+                2,
+                1,
+                "div"
+            ))
+        );
+
+        let text = "\
+nop +0
+acc one";
+        assert_eq!(
+            Instruction::parse(text),
+            Err(ParseError::new(ParseErrorKind::InvalidNumber, 2, 5, "one"))
+        );
+
+        assert_eq!(
+            Instruction::parse("jmp"),
+            Err(ParseError::new(ParseErrorKind::UnexpectedEnd, 1, 4, ""))
+        );
+
+        assert_eq!(
+            Instruction::parse("nop +0 +1"),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidCharacter,
+                1,
+                8,
+                "+1"
+            ))
+        );
+    }
+
     #[test]
     fn test_execute_once() {
         let text = "\
@@ -145,11 +397,11 @@ acc +1
 jmp -4
 acc +6";
 
-        let program = Instruction::parse(text);
+        let program = Instruction::parse(text).unwrap();
 
         let (acc, completed) = execute_once(&program);
         assert_eq!(acc, 5);
-        assert_eq!(completed, false);
+        assert!(!completed);
     }
 
     #[test]
@@ -159,7 +411,7 @@ nop +0
 acc +1
 jmp +4";
 
-        let program = Instruction::parse(text);
+        let program = Instruction::parse(text).unwrap();
 
         let mut mutated_programs = MutatedPrograms::new(program);
 
@@ -177,4 +429,67 @@ jmp +4";
         assert_eq!(*mutated_program.index(1), Instruction::Accumulate(1));
         assert_eq!(*mutated_program.index(2), Instruction::NoOperation(4));
     }
+
+    fn brute_force_repair(program: &[Instruction]) -> Option<i32> {
+        MutatedPrograms::new(program.to_vec())
+            .map(|mutated_program| execute_once(&mutated_program))
+            .find(|(_acc, completed)| *completed)
+            .map(|(acc, _completed)| acc)
+    }
+
+    #[test]
+    fn test_repair() {
+        let text = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+        let program = Instruction::parse(text).unwrap();
+
+        assert_eq!(
+            repair(&program),
+            Some(Repair {
+                index: 7,
+                instruction: Instruction::NoOperation(-4),
+                acc: 8
+            })
+        );
+        assert_eq!(brute_force_repair(&program), Some(8));
+    }
+
+    #[test]
+    fn test_repair_cross_check() {
+        let texts = [
+            "nop +2\njmp +0\njmp -1",
+            "jmp +2\nacc +5\nnop -1\nacc +1\njmp -2",
+            "acc +1\njmp +2\nacc +2\nnop +3\nacc +4\njmp -4\njmp -6",
+            "acc +1\nnop +1\njmp -2",
+            "acc +1\njnz +2\nnop +0\njmp -3",
+        ];
+        for text in texts.iter() {
+            let program = Instruction::parse(text).unwrap();
+            assert_eq!(
+                repair(&program).map(|repair| repair.acc),
+                brute_force_repair(&program),
+                "{}",
+                text
+            );
+        }
+
+        assert_eq!(repair(&Instruction::parse("acc +1").unwrap()), None);
+        assert_eq!(
+            Day08::part2(&Instruction::parse("acc +1").unwrap()),
+            "No single jmp/nop flip makes the program terminate"
+        );
+        assert_eq!(
+            repair(&Instruction::parse("jmp +0\nacc +1\njmp -1").unwrap()),
+            None
+        );
+    }
 }
@@ -15,11 +15,19 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
 use std::cmp::Ordering;
 use std::collections::VecDeque;
 
-pub fn parse(text: &str) -> Vec<i64> {
-    text.lines().map(|line| line.parse().unwrap()).collect()
+pub fn parse(text: &str) -> Result<Vec<i64>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|_| ParseError::new(ParseErrorKind::InvalidNumber, i + 1, 1, line))
+        })
+        .collect()
 }
 
 pub fn contains_match(preable: &VecDeque<i64>, needle: i64) -> bool {
@@ -70,6 +78,30 @@ pub fn exploit_weakness(weakness: i64, input: &[i64]) -> Option<i64> {
     None
 }
 
+pub struct Day09;
+
+impl Solver for Day09 {
+    type Input = Vec<i64>;
+
+    fn parse(text: &str) -> Result<Vec<i64>, ParseError> {
+        parse(text)
+    }
+
+    fn part1(numbers: &Vec<i64>) -> String {
+        match find_weakness(25, numbers) {
+            Some(weakness) => weakness.to_string(),
+            None => String::from("No number breaks the rule"),
+        }
+    }
+
+    fn part2(numbers: &Vec<i64>) -> String {
+        match find_weakness(25, numbers).and_then(|weakness| exploit_weakness(weakness, numbers)) {
+            Some(exploit) => exploit.to_string(),
+            None => String::from("No contiguous range sums to the weakness"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,11 +113,26 @@ mod tests {
 20
 15";
 
-        let numbers = parse(text);
+        let numbers = parse(text).unwrap();
         assert_eq!(numbers.len(), 3);
         assert_eq!(numbers[0], 35);
         assert_eq!(numbers[1], 20);
         assert_eq!(numbers[2], 15);
+
+        assert_eq!(
+            parse("35\n2O"),
+            Err(ParseError::new(ParseErrorKind::InvalidNumber, 2, 1, "2O"))
+        );
+    }
+
+    #[test]
+    fn test_no_weakness() {
+        let numbers: Vec<i64> = (1..=30).collect();
+        assert_eq!(Day09::part1(&numbers), "No number breaks the rule");
+        assert_eq!(
+            Day09::part2(&numbers),
+            "No contiguous range sums to the weakness"
+        );
     }
 
     #[test]
@@ -112,7 +159,7 @@ mod tests {
 309
 576";
 
-        let numbers = parse(text);
+        let numbers = parse(text).unwrap();
 
         let weakness = find_weakness(5, &numbers);
 
@@ -143,7 +190,7 @@ mod tests {
 309
 576";
 
-        let numbers = parse(text);
+        let numbers = parse(text).unwrap();
 
         let weakness = find_weakness(5, &numbers).unwrap();
         let exploit = exploit_weakness(weakness, &numbers);
@@ -15,15 +15,24 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
 use itertools::Itertools;
 use std::collections::HashMap;
 
-pub fn parse(text: &str) -> Vec<i32> {
-    let mut list: Vec<i32> = text.lines().map(|line| line.parse().unwrap()).collect();
+pub fn parse(text: &str) -> Result<Vec<i32>, ParseError> {
+    let mut list = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|_| ParseError::new(ParseErrorKind::InvalidNumber, i + 1, 1, line))
+        })
+        .collect::<Result<Vec<i32>, ParseError>>()?;
     list.push(0);
     list.push(*list.iter().max().unwrap() + 3);
     list.sort_unstable();
-    list
+    Ok(list)
 }
 
 pub fn find_diffs(input: &[i32]) -> HashMap<i32, usize> {
@@ -66,6 +75,26 @@ pub fn find_distinct_arrangements(input: &[i32], _start_index: Option<usize>) ->
     *paths_per_adapter.get(input.last().unwrap()).unwrap()
 }
 
+pub struct Day10;
+
+impl Solver for Day10 {
+    type Input = Vec<i32>;
+
+    fn parse(text: &str) -> Result<Vec<i32>, ParseError> {
+        parse(text)
+    }
+
+    fn part1(numbers: &Vec<i32>) -> String {
+        let diff_map = find_diffs(numbers);
+        let multiplied = diff_map.get(&1).unwrap_or(&0) * diff_map.get(&3).unwrap_or(&0);
+        multiplied.to_string()
+    }
+
+    fn part2(numbers: &Vec<i32>) -> String {
+        find_distinct_arrangements(numbers, None).to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,7 +107,7 @@ mod tests {
 131
 15";
 
-        let numbers = parse(text);
+        let numbers = parse(text).unwrap();
         assert_eq!(numbers.len(), 6);
         assert_eq!(numbers[0], 0);
         assert_eq!(numbers[1], 15);
@@ -86,6 +115,11 @@ mod tests {
         assert_eq!(numbers[3], 61);
         assert_eq!(numbers[4], 131);
         assert_eq!(numbers[5], 134);
+
+        assert_eq!(
+            parse("47\n-\n131"),
+            Err(ParseError::new(ParseErrorKind::InvalidNumber, 2, 1, "-"))
+        );
     }
 
     #[test]
@@ -103,7 +137,7 @@ mod tests {
 12
 4";
 
-        let numbers = parse(text);
+        let numbers = parse(text).unwrap();
         let diff_map = find_diffs(&numbers);
         assert_eq!(diff_map.get(&1), Some(&7));
         assert_eq!(diff_map.get(&3), Some(&5));
@@ -125,7 +159,7 @@ mod tests {
 1
 5";
 
-        let numbers = parse(text);
+        let numbers = parse(text).unwrap();
 
         let distinct_arrangements = find_distinct_arrangements_orig(&numbers, None);
         assert_eq!(distinct_arrangements, 4);
@@ -145,7 +179,7 @@ mod tests {
 6
 5";
 
-        let numbers = parse(text);
+        let numbers = parse(text).unwrap();
 
         let distinct_arrangements = find_distinct_arrangements_orig(&numbers, None);
         assert_eq!(distinct_arrangements, 7);
@@ -162,7 +196,7 @@ mod tests {
 6
 2";
 
-        let numbers = parse(text);
+        let numbers = parse(text).unwrap();
 
         let distinct_arrangements = find_distinct_arrangements_orig(&numbers, None);
         assert_eq!(distinct_arrangements, 4);
@@ -186,7 +220,7 @@ mod tests {
 11
 14";
 
-        let numbers = parse(text);
+        let numbers = parse(text).unwrap();
 
         let distinct_arrangements = find_distinct_arrangements_orig(&numbers, None);
         assert_eq!(distinct_arrangements, 28);
@@ -230,7 +264,7 @@ mod tests {
 10
 3";
 
-        let numbers = parse(text);
+        let numbers = parse(text).unwrap();
         let diff_map = find_diffs(&numbers);
         assert_eq!(diff_map.get(&1), Some(&22));
         assert_eq!(diff_map.get(&2), None);
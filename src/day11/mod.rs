@@ -15,6 +15,10 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::error::ParseError;
+use crate::grid::{Grid, Offset, Position, DIRECTIONS};
+use crate::Solver;
+
 #[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq)]
 enum Seat {
     EmptyFloor,
@@ -22,185 +26,86 @@ enum Seat {
     Occupied,
 }
 
-#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FloorPlan {
-    seats: Vec<Vec<Seat>>,
+    seats: Grid<Seat>,
 }
 
 impl FloorPlan {
-    pub fn parse(text: &str) -> FloorPlan {
-        FloorPlan {
-            seats: text
-                .lines()
-                .map(|line| {
-                    line.chars()
-                        .map(|char| match char {
-                            '.' => Seat::EmptyFloor,
-                            'L' => Seat::Empty,
-                            '#' => Seat::Occupied,
-                            _ => panic!(),
-                        })
-                        .collect()
-                })
-                .collect(),
-        }
+    pub fn parse(text: &str) -> Result<FloorPlan, ParseError> {
+        Ok(FloorPlan {
+            seats: Grid::parse(text, |char| match char {
+                '.' => Some(Seat::EmptyFloor),
+                'L' => Some(Seat::Empty),
+                '#' => Some(Seat::Occupied),
+                _ => None,
+            })?,
+        })
     }
 
-    fn get_seat(&self, x: usize, y: usize) -> Option<&Seat> {
-        self.seats.get(x).and_then(|row| row.get(y))
-    }
-
-    fn get_adjacent_seat(&self, x: usize, y: usize) -> Vec<&Seat> {
-        let mut coordinates = vec![];
-        if x > 0 {
-            if y > 0 {
-                coordinates.push((x - 1, y - 1));
-            }
-            coordinates.push((x - 1, y));
-            if y < self.seats[x - 1].len() {
-                coordinates.push((x - 1, y + 1));
-            }
-        }
-        if y > 0 {
-            coordinates.push((x, y - 1));
-        }
-        if y < self.seats[x].len() {
-            coordinates.push((x, y + 1));
-        }
-        if x < self.seats.len() - 1 {
-            if y > 0 {
-                coordinates.push((x + 1, y - 1));
-            }
-            coordinates.push((x + 1, y));
-            if y < self.seats[x + 1].len() {
-                coordinates.push((x + 1, y + 1));
-            }
-        }
-        coordinates
-            .iter()
-            .filter_map(|(x, y)| self.get_seat(*x, *y))
+    fn get_adjacent_seat(&self, position: Position) -> Vec<&Seat> {
+        self.seats
+            .neighbours8(position)
+            .map(|(_, seat)| seat)
             .collect()
     }
 
-    fn add_direction(pos: (usize, usize), direction: (isize, isize)) -> (usize, usize) {
-        if (direction.0 >= 0 || pos.0 > 0) && (direction.1 >= 0 || pos.1 > 0) {
-            (
-                (pos.0 as isize + direction.0) as usize,
-                (pos.1 as isize + direction.1) as usize,
-            )
-        } else {
-            (999999, 999999)
-        }
-    }
-
-    fn get_seen_seat(&self, seat: (usize, usize), direction: (isize, isize)) -> Option<&Seat> {
-        let mut seen_seat = Self::add_direction(seat, direction);
-        loop {
-            let option_seat = self.get_seat(seen_seat.0, seen_seat.1);
-            if let Some(seat) = option_seat {
-                if seat != &Seat::EmptyFloor {
-                    return option_seat;
-                }
-            } else {
-                return option_seat;
-            }
-            seen_seat = Self::add_direction(seen_seat, direction);
-        }
+    fn get_seen_seat(&self, position: Position, direction: Offset) -> Option<&Seat> {
+        self.seats
+            .ray(position, direction)
+            .map(|(_, seat)| seat)
+            .find(|&seat| seat != &Seat::EmptyFloor)
     }
 
-    fn get_seen_seats(&self, x: usize, y: usize) -> Vec<&Seat> {
-        let directions = vec![
-            (0, 1),
-            (0, -1),
-            (1, 1),
-            (1, -1),
-            (1, 0),
-            (-1, 1),
-            (-1, 0),
-            (-1, -1),
-        ];
-        directions
+    fn get_seen_seats(&self, position: Position) -> Vec<&Seat> {
+        DIRECTIONS
             .iter()
-            .filter_map(|direction| self.get_seen_seat((x, y), *direction))
+            .filter_map(|&direction| self.get_seen_seat(position, direction))
             .collect()
     }
 
-    pub fn get_number_of_seen_occupied_seat(&self, x: usize, y: usize) -> usize {
-        self.get_seen_seats(x, y)
+    pub fn get_number_of_seen_occupied_seat(&self, position: Position) -> usize {
+        self.get_seen_seats(position)
             .iter()
             .filter(|&&&seat| seat == Seat::Occupied)
             .count()
     }
 
-    pub fn get_number_of_adjacent_occupied_seat(&self, x: usize, y: usize) -> usize {
-        self.get_adjacent_seat(x, y)
+    pub fn get_number_of_adjacent_occupied_seat(&self, position: Position) -> usize {
+        self.get_adjacent_seat(position)
             .iter()
             .filter(|&&&seat| seat == Seat::Occupied)
             .count()
     }
 
-    pub fn get_evolution(&self) -> Self {
+    fn evolve(&self, count_occupied: fn(&Self, Position) -> usize, tolerance: usize) -> Self {
         FloorPlan {
-            seats: self
-                .seats
-                .iter()
-                .enumerate()
-                .map(|(x, row)| {
-                    row.iter()
-                        .enumerate()
-                        .map(|(y, seat)| match seat {
-                            Seat::EmptyFloor => Seat::EmptyFloor,
-                            Seat::Occupied => {
-                                if self.get_number_of_adjacent_occupied_seat(x, y) >= 4 {
-                                    Seat::Empty
-                                } else {
-                                    Seat::Occupied
-                                }
-                            }
-                            Seat::Empty => {
-                                if self.get_number_of_adjacent_occupied_seat(x, y) == 0 {
-                                    Seat::Occupied
-                                } else {
-                                    Seat::Empty
-                                }
-                            }
-                        })
-                        .collect()
-                })
-                .collect(),
+            seats: self.seats.map(|position, seat| match seat {
+                Seat::EmptyFloor => Seat::EmptyFloor,
+                Seat::Occupied => {
+                    if count_occupied(self, position) >= tolerance {
+                        Seat::Empty
+                    } else {
+                        Seat::Occupied
+                    }
+                }
+                Seat::Empty => {
+                    if count_occupied(self, position) == 0 {
+                        Seat::Occupied
+                    } else {
+                        Seat::Empty
+                    }
+                }
+            }),
         }
     }
 
+    pub fn get_evolution(&self) -> Self {
+        self.evolve(Self::get_number_of_adjacent_occupied_seat, 4)
+    }
+
     pub fn get_evolution_part2(&self) -> Self {
-        FloorPlan {
-            seats: self
-                .seats
-                .iter()
-                .enumerate()
-                .map(|(x, row)| {
-                    row.iter()
-                        .enumerate()
-                        .map(|(y, seat)| match seat {
-                            Seat::EmptyFloor => Seat::EmptyFloor,
-                            Seat::Occupied => {
-                                if self.get_number_of_seen_occupied_seat(x, y) >= 5 {
-                                    Seat::Empty
-                                } else {
-                                    Seat::Occupied
-                                }
-                            }
-                            Seat::Empty => {
-                                if self.get_number_of_seen_occupied_seat(x, y) == 0 {
-                                    Seat::Occupied
-                                } else {
-                                    Seat::Empty
-                                }
-                            }
-                        })
-                        .collect()
-                })
-                .collect(),
-        }
+        self.evolve(Self::get_number_of_seen_occupied_seat, 5)
     }
 
     pub fn get_stable_evolution(&self) -> Self {
@@ -228,14 +133,35 @@ impl FloorPlan {
     pub fn get_number_of_occupied_seat(&self) -> usize {
         self.seats
             .iter()
-            .map(|row| row.iter().filter(|&&seat| seat == Seat::Occupied).count())
-            .sum()
+            .filter(|(_, &seat)| seat == Seat::Occupied)
+            .count()
+    }
+}
+
+pub struct Day11;
+
+impl Solver for Day11 {
+    type Input = FloorPlan;
+
+    fn parse(text: &str) -> Result<FloorPlan, ParseError> {
+        FloorPlan::parse(text)
+    }
+
+    fn part1(floor_plan: &FloorPlan) -> String {
+        let evolution = floor_plan.get_stable_evolution();
+        evolution.get_number_of_occupied_seat().to_string()
+    }
+
+    fn part2(floor_plan: &FloorPlan) -> String {
+        let evolution = floor_plan.get_stable_evolution_part2();
+        evolution.get_number_of_occupied_seat().to_string()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::ParseErrorKind;
 
     #[test]
     fn test_parse() {
@@ -243,16 +169,20 @@ mod tests {
 L.#
 LLL";
 
-        let floor_plan = FloorPlan::parse(text);
-        assert_eq!(floor_plan.seats.len(), 2);
-        assert_eq!(floor_plan.seats[0].len(), 3);
-        assert_eq!(floor_plan.seats[0][0], Seat::Empty);
-        assert_eq!(floor_plan.seats[0][1], Seat::EmptyFloor);
-        assert_eq!(floor_plan.seats[0][2], Seat::Occupied);
-        assert_eq!(floor_plan.seats[1].len(), 3);
-        assert_eq!(floor_plan.seats[1][0], Seat::Empty);
-        assert_eq!(floor_plan.seats[1][1], Seat::Empty);
-        assert_eq!(floor_plan.seats[1][2], Seat::Empty);
+        let floor_plan = FloorPlan::parse(text).unwrap();
+        assert_eq!(floor_plan.seats.height(), 2);
+        assert_eq!(floor_plan.seats.width(), 3);
+        assert_eq!(floor_plan.seats[(0, 0)], Seat::Empty);
+        assert_eq!(floor_plan.seats[(1, 0)], Seat::EmptyFloor);
+        assert_eq!(floor_plan.seats[(2, 0)], Seat::Occupied);
+        assert_eq!(floor_plan.seats[(0, 1)], Seat::Empty);
+        assert_eq!(floor_plan.seats[(1, 1)], Seat::Empty);
+        assert_eq!(floor_plan.seats[(2, 1)], Seat::Empty);
+
+        assert_eq!(
+            FloorPlan::parse("L.#\nLxL"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 2, 2, "x"))
+        );
     }
 
     #[test]
@@ -262,8 +192,8 @@ LLL";
 .L#
 LLL";
 
-        let floor_plan = FloorPlan::parse(text);
-        let adjacent_seats = floor_plan.get_adjacent_seat(1, 1);
+        let floor_plan = FloorPlan::parse(text).unwrap();
+        let adjacent_seats = floor_plan.get_adjacent_seat((1, 1));
         assert_eq!(adjacent_seats.len(), 8);
         assert_eq!(adjacent_seats[0], &Seat::Occupied);
         assert_eq!(adjacent_seats[1], &Seat::Occupied);
@@ -289,7 +219,7 @@ L.#.#..#..
 #.LL###L.L
 #.#L###.##";
 
-        let floor_plan = FloorPlan::parse(text);
+        let floor_plan = FloorPlan::parse(text).unwrap();
         let evolution = floor_plan.get_evolution();
 
         let expected_text = "\
@@ -303,7 +233,7 @@ L.L.L..#..
 #L#LLLL#L#
 #.LLLLLL.L
 #.#L#L#.##";
-        let expected_floor_plan = FloorPlan::parse(expected_text);
+        let expected_floor_plan = FloorPlan::parse(expected_text).unwrap();
         assert_eq!(evolution, expected_floor_plan);
     }
 
@@ -321,7 +251,7 @@ LLLLLLLLLL
 L.LLLLLL.L
 L.LLLLL.LL";
 
-        let floor_plan = FloorPlan::parse(text);
+        let floor_plan = FloorPlan::parse(text).unwrap();
         let evolution = floor_plan.get_stable_evolution();
 
         let expected_text = "\
@@ -335,7 +265,7 @@ L.#.L..#..
 #L#L##L#L#
 #.LLLLLL.L
 #.#L#L#.##";
-        let expected_floor_plan = FloorPlan::parse(expected_text);
+        let expected_floor_plan = FloorPlan::parse(expected_text).unwrap();
         assert_eq!(evolution, expected_floor_plan);
 
         let occupied_seats = evolution.get_number_of_occupied_seat();
@@ -355,8 +285,8 @@ L.#.L..#..
 #........
 ...#.....";
 
-        let floor_plan = FloorPlan::parse(text);
-        let adjacent_seats = floor_plan.get_seen_seats(4, 3);
+        let floor_plan = FloorPlan::parse(text).unwrap();
+        let adjacent_seats = floor_plan.get_seen_seats((3, 4));
         assert_eq!(adjacent_seats.len(), 8);
         assert_eq!(adjacent_seats[0], &Seat::Occupied);
         assert_eq!(adjacent_seats[1], &Seat::Occupied);
@@ -375,8 +305,8 @@ L.#.L..#..
 .L.L.#.#.#.#.
 .............";
 
-        let floor_plan = FloorPlan::parse(text);
-        let adjacent_seats = floor_plan.get_seen_seats(1, 1);
+        let floor_plan = FloorPlan::parse(text).unwrap();
+        let adjacent_seats = floor_plan.get_seen_seats((1, 1));
         assert_eq!(adjacent_seats.len(), 1);
         assert_eq!(adjacent_seats[0], &Seat::Empty);
     }
@@ -395,7 +325,7 @@ LLLLLLLLLL
 L.LLLLLL.L
 L.LLLLL.LL";
 
-        let floor_plan = FloorPlan::parse(text);
+        let floor_plan = FloorPlan::parse(text).unwrap();
         let evolution = floor_plan.get_stable_evolution_part2();
 
         let expected_text = "\
@@ -409,7 +339,7 @@ L.L#.LL.L#
 LLL###LLL#
 #.LLLLL#.L
 #.L#LL#.L#";
-        let expected_floor_plan = FloorPlan::parse(expected_text);
+        let expected_floor_plan = FloorPlan::parse(expected_text).unwrap();
         assert_eq!(evolution, expected_floor_plan);
 
         let occupied_seats = evolution.get_number_of_occupied_seat();
@@ -15,6 +15,9 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+
 pub struct OwnShip {
     pos_east: i32,
     pos_north: i32,
@@ -40,59 +43,89 @@ impl OwnShip {
         }
     }
 
-    pub fn execute(&mut self, instruction: &str) {
-        let direction = &instruction[0..1];
-        let amount: i32 = instruction[1..].parse().unwrap();
+    fn parse_instruction(instruction: &str) -> Result<(char, i32), ParseError> {
+        let direction = instruction
+            .chars()
+            .next()
+            .ok_or_else(|| ParseError::new(ParseErrorKind::UnexpectedEnd, 1, 1, instruction))?;
+        if !"NSEWLRF".contains(direction) {
+            return Err(ParseError::in_line(
+                ParseErrorKind::UnknownInstruction,
+                1,
+                instruction,
+                &instruction[..direction.len_utf8()],
+            ));
+        }
+
+        let amount_text = &instruction[direction.len_utf8()..];
+        let amount: i32 = amount_text.parse().map_err(|_| {
+            ParseError::in_line(ParseErrorKind::InvalidNumber, 1, instruction, amount_text)
+        })?;
+        if (direction == 'L' || direction == 'R') && ![90, 180, 270].contains(&amount) {
+            return Err(ParseError::in_line(
+                ParseErrorKind::InvalidRotation,
+                1,
+                instruction,
+                amount_text,
+            ));
+        }
+
+        Ok((direction, amount))
+    }
+
+    pub fn execute(&mut self, instruction: &str) -> Result<(), ParseError> {
+        let (direction, amount) = Self::parse_instruction(instruction)?;
 
         match direction {
-            "N" => self.pos_north += amount,
-            "S" => self.pos_north -= amount,
-            "E" => self.pos_east += amount,
-            "W" => self.pos_east -= amount,
-            "L" => {
+            'N' => self.pos_north += amount,
+            'S' => self.pos_north -= amount,
+            'E' => self.pos_east += amount,
+            'W' => self.pos_east -= amount,
+            'L' => {
                 self.heading -= amount;
                 if self.heading < 0 {
                     self.heading += 360
                 }
             }
-            "R" => {
+            'R' => {
                 self.heading += amount;
                 if self.heading >= 360 {
                     self.heading -= 360
                 }
             }
-            "F" => match self.heading {
+            'F' => match self.heading {
                 0 => self.pos_north += amount,
                 90 => self.pos_east += amount,
                 180 => self.pos_north -= amount,
                 270 => self.pos_east -= amount,
-                _ => panic!(),
+                _ => unreachable!(),
             },
-            _ => panic!(),
+            _ => unreachable!(),
         }
+        Ok(())
     }
 
-    pub fn execute_part2(&mut self, instruction: &str) {
-        let direction = &instruction[0..1];
-        let amount: i32 = instruction[1..].parse().unwrap();
+    pub fn execute_part2(&mut self, instruction: &str) -> Result<(), ParseError> {
+        let (direction, amount) = Self::parse_instruction(instruction)?;
 
         match direction {
-            "N" => self.waypoint_north += amount,
-            "S" => self.waypoint_north -= amount,
-            "E" => self.waypoint_east += amount,
-            "W" => self.waypoint_east -= amount,
-            "L" => {
+            'N' => self.waypoint_north += amount,
+            'S' => self.waypoint_north -= amount,
+            'E' => self.waypoint_east += amount,
+            'W' => self.waypoint_east -= amount,
+            'L' => {
                 self.rotate_waypoint(360 - amount);
             }
-            "R" => {
+            'R' => {
                 self.rotate_waypoint(amount);
             }
-            "F" => {
+            'F' => {
                 self.pos_east += self.waypoint_east * amount;
                 self.pos_north += self.waypoint_north * amount;
             }
-            _ => panic!(),
+            _ => unreachable!(),
         }
+        Ok(())
     }
 
     fn rotate_waypoint(&mut self, rotation: i32) {
@@ -111,7 +144,7 @@ impl OwnShip {
                 self.waypoint_east = -old_north;
                 self.waypoint_north = old_east
             }
-            _ => panic!(),
+            _ => unreachable!(),
         }
     }
 
@@ -120,6 +153,39 @@ impl OwnShip {
     }
 }
 
+pub struct Day12;
+
+impl Solver for Day12 {
+    type Input = Vec<String>;
+
+    fn parse(text: &str) -> Result<Vec<String>, ParseError> {
+        text.lines()
+            .enumerate()
+            .map(|(i, line)| {
+                OwnShip::parse_instruction(line)
+                    .map(|_| String::from(line))
+                    .map_err(|error| error.at_line(i + 1))
+            })
+            .collect()
+    }
+
+    fn part1(instructions: &Vec<String>) -> String {
+        let mut own_ship = OwnShip::new();
+        instructions.iter().for_each(|instruction| {
+            own_ship.execute(instruction).unwrap();
+        });
+        own_ship.get_manhattan_distance().to_string()
+    }
+
+    fn part2(instructions: &Vec<String>) -> String {
+        let mut own_ship = OwnShip::new();
+        instructions.iter().for_each(|instruction| {
+            own_ship.execute_part2(instruction).unwrap();
+        });
+        own_ship.get_manhattan_distance().to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,27 +194,27 @@ mod tests {
     fn test_instructions() {
         let mut own_ship = OwnShip::new();
 
-        own_ship.execute("F10");
+        own_ship.execute("F10").unwrap();
         assert_eq!(own_ship.pos_east, 10);
         assert_eq!(own_ship.pos_north, 0);
         assert_eq!(own_ship.heading, 90);
 
-        own_ship.execute("N3");
+        own_ship.execute("N3").unwrap();
         assert_eq!(own_ship.pos_east, 10);
         assert_eq!(own_ship.pos_north, 3);
         assert_eq!(own_ship.heading, 90);
 
-        own_ship.execute("F7");
+        own_ship.execute("F7").unwrap();
         assert_eq!(own_ship.pos_east, 17);
         assert_eq!(own_ship.pos_north, 3);
         assert_eq!(own_ship.heading, 90);
 
-        own_ship.execute("R90");
+        own_ship.execute("R90").unwrap();
         assert_eq!(own_ship.pos_east, 17);
         assert_eq!(own_ship.pos_north, 3);
         assert_eq!(own_ship.heading, 180);
 
-        own_ship.execute("F11");
+        own_ship.execute("F11").unwrap();
         assert_eq!(own_ship.pos_east, 17);
         assert_eq!(own_ship.pos_north, -8);
         assert_eq!(own_ship.heading, 180);
@@ -161,31 +227,31 @@ mod tests {
     fn test_instructions_part2() {
         let mut own_ship = OwnShip::new();
 
-        own_ship.execute_part2("F10");
+        own_ship.execute_part2("F10").unwrap();
         assert_eq!(own_ship.pos_east, 100);
         assert_eq!(own_ship.pos_north, 10);
         assert_eq!(own_ship.waypoint_east, 10);
         assert_eq!(own_ship.waypoint_north, 1);
 
-        own_ship.execute_part2("N3");
+        own_ship.execute_part2("N3").unwrap();
         assert_eq!(own_ship.pos_east, 100);
         assert_eq!(own_ship.pos_north, 10);
         assert_eq!(own_ship.waypoint_east, 10);
         assert_eq!(own_ship.waypoint_north, 4);
 
-        own_ship.execute_part2("F7");
+        own_ship.execute_part2("F7").unwrap();
         assert_eq!(own_ship.pos_east, 170);
         assert_eq!(own_ship.pos_north, 38);
         assert_eq!(own_ship.waypoint_east, 10);
         assert_eq!(own_ship.waypoint_north, 4);
 
-        own_ship.execute_part2("R90");
+        own_ship.execute_part2("R90").unwrap();
         assert_eq!(own_ship.pos_east, 170);
         assert_eq!(own_ship.pos_north, 38);
         assert_eq!(own_ship.waypoint_east, 4);
         assert_eq!(own_ship.waypoint_north, -10);
 
-        own_ship.execute_part2("F11");
+        own_ship.execute_part2("F11").unwrap();
         assert_eq!(own_ship.pos_east, 214);
         assert_eq!(own_ship.pos_north, -72);
         assert_eq!(own_ship.waypoint_east, 4);
@@ -194,4 +260,32 @@ mod tests {
         let distance = own_ship.get_manhattan_distance();
         assert_eq!(distance, 286);
     }
+
+    #[test]
+    fn test_invalid_instructions() {
+        let mut own_ship = OwnShip::new();
+
+        assert_eq!(
+            own_ship.execute("X10"),
+            Err(ParseError::new(
+                ParseErrorKind::UnknownInstruction,
+                1,
+                1,
+                "X"
+            ))
+        );
+        assert_eq!(
+            own_ship.execute("F1O"),
+            Err(ParseError::new(ParseErrorKind::InvalidNumber, 1, 2, "1O"))
+        );
+        assert_eq!(
+            own_ship.execute("R45"),
+            Err(ParseError::new(ParseErrorKind::InvalidRotation, 1, 2, "45"))
+        );
+        assert_eq!(
+            own_ship.execute_part2(""),
+            Err(ParseError::new(ParseErrorKind::UnexpectedEnd, 1, 1, ""))
+        );
+        assert_eq!(own_ship.get_manhattan_distance(), 0);
+    }
 }
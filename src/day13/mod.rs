@@ -15,6 +15,8 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
 use nom::lib::std::collections::BTreeMap;
 use std::cmp::max;
 
@@ -24,27 +26,31 @@ pub struct Schedule {
 }
 
 impl Schedule {
-    pub fn parse(text: &str) -> Schedule {
+    pub fn parse(text: &str) -> Result<Schedule, ParseError> {
         let mut lines = text.lines();
-        let depart_time = lines.next().unwrap().parse().unwrap();
-        let available_busses = lines
+        let depart_line = lines
             .next()
-            .unwrap()
+            .ok_or_else(|| ParseError::new(ParseErrorKind::UnexpectedEnd, 1, 1, ""))?;
+        let depart_time = depart_line
+            .parse()
+            .map_err(|_| ParseError::new(ParseErrorKind::InvalidNumber, 1, 1, depart_line))?;
+        let bus_line = lines
+            .next()
+            .ok_or_else(|| ParseError::new(ParseErrorKind::UnexpectedEnd, 2, 1, ""))?;
+        let available_busses = bus_line
             .split(',')
             .enumerate()
-            .filter_map(|(i, text)| {
-                let optional_number = text.parse().ok();
-                if let Some(number) = optional_number {
-                    Some((i as u64, number))
-                } else {
-                    None
-                }
+            .filter(|(_i, text)| *text != "x")
+            .map(|(i, text)| {
+                text.parse().map(|number| (i as u64, number)).map_err(|_| {
+                    ParseError::in_line(ParseErrorKind::InvalidNumber, 2, bus_line, text)
+                })
             })
-            .collect();
-        Schedule {
+            .collect::<Result<_, _>>()?;
+        Ok(Schedule {
             depart_time,
             available_busses,
-        }
+        })
     }
 
     pub fn get_earliest_bus(&self) -> (u64, u64) {
@@ -88,6 +94,25 @@ impl Schedule {
     }
 }
 
+pub struct Day13;
+
+impl Solver for Day13 {
+    type Input = Schedule;
+
+    fn parse(text: &str) -> Result<Schedule, ParseError> {
+        Schedule::parse(text)
+    }
+
+    fn part1(schedule: &Schedule) -> String {
+        let (time_till_next_bus, available_bus) = schedule.get_earliest_bus();
+        (time_till_next_bus * available_bus).to_string()
+    }
+
+    fn part2(schedule: &Schedule) -> String {
+        schedule.get_contest_answer().to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,7 +123,7 @@ mod tests {
 939
 7,13,x,x,59,x,31,19";
 
-        let schedule = Schedule::parse(text);
+        let schedule = Schedule::parse(text).unwrap();
 
         assert_eq!(schedule.depart_time, 939);
         let available_busses: Vec<&u64> = schedule.available_busses.values().collect();
@@ -115,7 +140,7 @@ mod tests {
 939
 17,x,13,19";
 
-        let schedule = Schedule::parse(text);
+        let schedule = Schedule::parse(text).unwrap();
         assert_eq!(schedule.get_contest_answer(), 3417);
     }
 
@@ -125,7 +150,23 @@ mod tests {
 939
 7,13,x,x,59,x,31,19";
 
-        let schedule = Schedule::parse(text);
+        let schedule = Schedule::parse(text).unwrap();
         assert_eq!(schedule.get_contest_answer(), 1068781);
     }
+
+    #[test]
+    fn test_parse_invalid() {
+        let text = "\
+939
+7,13,x,y,59";
+        assert_eq!(
+            Schedule::parse(text).err(),
+            Some(ParseError::new(ParseErrorKind::InvalidNumber, 2, 8, "y"))
+        );
+
+        assert_eq!(
+            Schedule::parse("939").err(),
+            Some(ParseError::new(ParseErrorKind::UnexpectedEnd, 2, 1, ""))
+        );
+    }
 }
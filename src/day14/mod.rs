@@ -15,39 +15,113 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
 use nom::lib::std::collections::BTreeMap;
+use regex::Regex;
+
+/// A line of the initialization program. Masks are 36 characters of `0`, `1` and `X`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Instruction {
+    Mask(String),
+    Write { address: usize, value: u64 },
+}
+
+impl Instruction {
+    fn parse_mask(line_number: usize, line: &str) -> Result<&str, ParseError> {
+        let mask = &line["mask = ".len()..];
+        if mask.len() != 36 {
+            return Err(ParseError::in_line(
+                ParseErrorKind::InvalidLength,
+                line_number,
+                line,
+                mask,
+            ));
+        }
+        if let Some((i, c)) = mask.char_indices().find(|(_, c)| !"01X".contains(*c)) {
+            return Err(ParseError::in_line(
+                ParseErrorKind::InvalidCharacter,
+                line_number,
+                line,
+                &mask[i..i + c.len_utf8()],
+            ));
+        }
+        Ok(mask)
+    }
+
+    fn parse_write(re: &Regex, line_number: usize, line: &str) -> Result<(usize, u64), ParseError> {
+        let captures = re.captures(line).ok_or_else(|| {
+            ParseError::new(ParseErrorKind::UnknownInstruction, line_number, 1, line)
+        })?;
+        let address = captures.get(1).unwrap().as_str();
+        let address = address.parse().map_err(|_| {
+            ParseError::in_line(ParseErrorKind::InvalidNumber, line_number, line, address)
+        })?;
+        let value = captures.get(2).unwrap().as_str();
+        let value = value.parse().map_err(|_| {
+            ParseError::in_line(ParseErrorKind::InvalidNumber, line_number, line, value)
+        })?;
+        Ok((address, value))
+    }
+
+    /// Parses the program, which has to set a mask before the first write.
+    pub fn parse(text: &str) -> Result<Vec<Instruction>, ParseError> {
+        let re = Regex::new(r"^mem\[(\d+)\] = (\d+)$").unwrap();
+        let mut program = vec![];
+        for (line_index, line) in text.lines().enumerate() {
+            if line.starts_with("mask = ") {
+                let mask = Self::parse_mask(line_index + 1, line)?;
+                program.push(Instruction::Mask(String::from(mask)));
+            } else {
+                let (address, value) = Self::parse_write(&re, line_index + 1, line)?;
+                if program.is_empty() {
+                    return Err(ParseError::new(
+                        ParseErrorKind::InvalidCharacter,
+                        line_index + 1,
+                        1,
+                        "mem",
+                    ));
+                }
+                program.push(Instruction::Write { address, value });
+            }
+        }
+        Ok(program)
+    }
+}
 
 pub struct Memory {
     memory_map: BTreeMap<usize, u64>,
 }
 
 impl Memory {
-    pub fn parse(text: &str) -> Memory {
+    /// Runs the program with the version 1 decoder, which masks the values.
+    pub fn run(program: &[Instruction]) -> Memory {
         let mut memory = Memory {
             memory_map: BTreeMap::new(),
         };
+        // Until the program sets a mask, values are written unchanged
         let mut or_mask: u64 = 0;
-        let mut and_mask: u64 = 0;
+        let mut and_mask: u64 = u64::MAX;
 
-        for line in text.lines() {
-            let split: Vec<&str> = line.split(" = ").collect();
-            if split[0] == "mask" {
-                and_mask = u64::from_str_radix(split[1].replace('X', "1").as_str(), 2).unwrap();
-                or_mask = u64::from_str_radix(split[1].replace('X', "0").as_str(), 2).unwrap();
-            } else {
-                use regex::Regex;
-                let re = Regex::new(r"^mem\[(\d+)\] = (\d+)$").unwrap();
-                let captures = re.captures(line).unwrap();
-                let address = captures[1].parse().unwrap();
-                let value: u64 = captures[2].parse().unwrap();
-
-                let value = value & and_mask | or_mask;
-                memory.memory_map.insert(address, value);
+        for instruction in program {
+            match instruction {
+                Instruction::Mask(mask) => {
+                    and_mask = u64::from_str_radix(mask.replace('X', "1").as_str(), 2).unwrap();
+                    or_mask = u64::from_str_radix(mask.replace('X', "0").as_str(), 2).unwrap();
+                }
+                Instruction::Write { address, value } => {
+                    let value = value & and_mask | or_mask;
+                    memory.memory_map.insert(*address, value);
+                }
             }
         }
         memory
     }
 
+    pub fn parse(text: &str) -> Result<Memory, ParseError> {
+        Ok(Memory::run(&Instruction::parse(text)?))
+    }
+
     fn get_mask_variations(mask: String) -> Vec<String> {
         if mask.find('F').is_some() {
             [
@@ -75,40 +149,58 @@ impl Memory {
         let or_mask = usize::from_str_radix(mask.replace('X', "0").as_str(), 2).unwrap();
         let address = base_address | or_mask;
 
-        let remaining_mask = mask.replace('X', "F").replace('1', "X").replace('0', "X");
+        let remaining_mask = mask.replace('X', "F").replace(['1', '0'], "X");
         Self::get_floating_address_variations(address, remaining_mask)
     }
 
-    pub fn parse_part2(text: &str) -> Memory {
+    /// Runs the program with the version 2 decoder, which masks the addresses.
+    pub fn run_part2(program: &[Instruction]) -> Memory {
         let mut memory = Memory {
             memory_map: BTreeMap::new(),
         };
-        let mut mask: String = String::new();
+        // Until the program sets a mask, addresses are used unchanged
+        let mut mask = "0".repeat(36);
 
-        for line in text.lines() {
-            let split: Vec<&str> = line.split(" = ").collect();
-            if split[0] == "mask" {
-                mask = String::from(split[1]);
-            } else {
-                use regex::Regex;
-                let re = Regex::new(r"^mem\[(\d+)\] = (\d+)$").unwrap();
-                let captures = re.captures(line).unwrap();
-                let base_address = captures[1].parse().unwrap();
-                let value: u64 = captures[2].parse().unwrap();
-
-                for address in Self::get_address_variations(base_address, mask.clone()) {
-                    memory.memory_map.insert(address, value);
+        for instruction in program {
+            match instruction {
+                Instruction::Mask(new_mask) => mask = new_mask.clone(),
+                Instruction::Write { address, value } => {
+                    for address in Self::get_address_variations(*address, mask.clone()) {
+                        memory.memory_map.insert(address, *value);
+                    }
                 }
             }
         }
         memory
     }
 
+    pub fn parse_part2(text: &str) -> Result<Memory, ParseError> {
+        Ok(Memory::run_part2(&Instruction::parse(text)?))
+    }
+
     pub fn get_sum(&self) -> u64 {
         self.memory_map.values().sum()
     }
 }
 
+pub struct Day14;
+
+impl Solver for Day14 {
+    type Input = Vec<Instruction>;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        Instruction::parse(text)
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        Memory::run(program).get_sum().to_string()
+    }
+
+    fn part2(program: &Self::Input) -> String {
+        Memory::run_part2(program).get_sum().to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,7 +212,7 @@ mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
 mem[8] = 11
 mem[7] = 101
 mem[8] = 0";
-        let memory = Memory::parse(text);
+        let memory = Memory::parse(text).unwrap();
         assert_eq!(memory.get_sum(), 165);
     }
 
@@ -131,7 +223,7 @@ mask = 000000000000000000000000000000X1001X
 mem[42] = 100
 mask = 00000000000000000000000000000000X0XX
 mem[26] = 1";
-        let memory = Memory::parse_part2(text);
+        let memory = Memory::parse_part2(text).unwrap();
         assert_eq!(memory.get_sum(), 208);
     }
 
@@ -142,7 +234,7 @@ mask = 0XX000X1111001010X10XX1101XX00X00100
 mem[50596] = 1000
 mask = 0X000001111001010X1011100100001X0X0X
 mem[45713] = 1";
-        let memory = Memory::parse_part2(text);
+        let memory = Memory::parse_part2(text).unwrap();
         assert_eq!(memory.get_sum(), 508032);
     }
 
@@ -153,7 +245,76 @@ mask = 000000000000000000000000000000000XXX
 mem[8] = 4
 mask = XX0000000000000000000000000000000000
 mem[0] = 5";
-        let memory = Memory::parse_part2(text);
+        let memory = Memory::parse_part2(text).unwrap();
         assert_eq!(memory.get_sum(), 52);
     }
+
+    #[test]
+    fn test_parse_program() {
+        let text = "\
+mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11";
+        let program = Instruction::parse(text).unwrap();
+        assert_eq!(
+            program,
+            [
+                Instruction::Mask(String::from("XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X")),
+                Instruction::Write {
+                    address: 8,
+                    value: 11
+                }
+            ]
+        );
+        assert_eq!(Day14::part1(&program), "73");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let text = "\
+mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mem[7] = -101";
+        assert_eq!(
+            Memory::parse(text).err(),
+            Some(ParseError::new(
+                ParseErrorKind::UnknownInstruction,
+                3,
+                1,
+                "mem[7] = -101"
+            ))
+        );
+
+        let text = "\
+mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX2X";
+        assert_eq!(
+            Memory::parse_part2(text).err(),
+            Some(ParseError::new(
+                ParseErrorKind::InvalidCharacter,
+                1,
+                42,
+                "2"
+            ))
+        );
+
+        let text = "\
+mem[8] = 11
+mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X";
+        let error = ParseError::new(ParseErrorKind::InvalidCharacter, 1, 1, "mem");
+        assert_eq!(Memory::parse(text).err(), Some(error.clone()));
+        assert_eq!(Memory::parse_part2(text).err(), Some(error));
+
+        let program = [Instruction::Write {
+            address: 8,
+            value: 11,
+        }];
+        assert_eq!(Memory::run(&program).get_sum(), 11);
+        assert_eq!(Memory::run_part2(&program).get_sum(), 11);
+
+        let text = "\
+mask = XXXX";
+        assert_eq!(
+            Memory::parse(text).err(),
+            Some(ParseError::new(ParseErrorKind::InvalidLength, 1, 8, "XXXX"))
+        );
+    }
 }
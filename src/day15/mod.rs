@@ -15,6 +15,8 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
 use itertools::Itertools;
 use nom::lib::std::collections::HashMap;
 
@@ -87,6 +89,33 @@ impl MemoryGame {
     }
 }
 
+pub struct Day15;
+
+impl Solver for Day15 {
+    type Input = Vec<u32>;
+
+    fn parse(text: &str) -> Result<Vec<u32>, ParseError> {
+        let line = text.trim();
+        line.split(',')
+            .map(|number| {
+                number.parse().map_err(|_| {
+                    ParseError::in_line(ParseErrorKind::InvalidNumber, 1, line, number)
+                })
+            })
+            .collect()
+    }
+
+    fn part1(starting_numbers: &Vec<u32>) -> String {
+        let mut game = MemoryGame::new(starting_numbers);
+        game.speak_nth_number(2020).spoken_number.to_string()
+    }
+
+    fn part2(starting_numbers: &Vec<u32>) -> String {
+        let mut game = MemoryGame::new(starting_numbers);
+        game.speak_nth_number(30000000).spoken_number.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,276 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use std::ops::RangeInclusive;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    ranges: [RangeInclusive<u64>; 2],
+}
+
+impl Rule {
+    fn parse(line_number: usize, line: &str) -> Result<Rule, ParseError> {
+        let (name, ranges) = line.split_once(": ").ok_or_else(|| {
+            ParseError::new(ParseErrorKind::MissingSeparator(':'), line_number, 1, line)
+        })?;
+        let (first, second) = ranges.split_once(" or ").ok_or_else(|| {
+            ParseError::in_line(ParseErrorKind::InvalidCharacter, line_number, line, ranges)
+        })?;
+        let parse_range = |range: &str| -> Result<RangeInclusive<u64>, ParseError> {
+            let (start, end) = range.split_once('-').ok_or_else(|| {
+                ParseError::in_line(
+                    ParseErrorKind::MissingSeparator('-'),
+                    line_number,
+                    line,
+                    range,
+                )
+            })?;
+            let parse_number = |number: &str| -> Result<u64, ParseError> {
+                number.parse().map_err(|_| {
+                    ParseError::in_line(ParseErrorKind::InvalidNumber, line_number, line, number)
+                })
+            };
+            Ok(parse_number(start)?..=parse_number(end)?)
+        };
+        Ok(Rule {
+            name: String::from(name),
+            ranges: [parse_range(first)?, parse_range(second)?],
+        })
+    }
+
+    pub fn is_valid(&self, value: u64) -> bool {
+        self.ranges.iter().any(|range| range.contains(&value))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Notes {
+    pub rules: Vec<Rule>,
+    pub your_ticket: Vec<u64>,
+    pub nearby_tickets: Vec<Vec<u64>>,
+}
+
+fn parse_ticket(line_number: usize, line: &str) -> Result<Vec<u64>, ParseError> {
+    line.split(',')
+        .map(|number| {
+            number.parse().map_err(|_| {
+                ParseError::in_line(ParseErrorKind::InvalidNumber, line_number, line, number)
+            })
+        })
+        .collect()
+}
+
+fn next_line<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    end_line: usize,
+) -> Result<(usize, &'a str), ParseError> {
+    lines
+        .next()
+        .ok_or_else(|| ParseError::new(ParseErrorKind::UnexpectedEnd, end_line, 1, ""))
+}
+
+fn expect_line<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    expected: &str,
+    end_line: usize,
+) -> Result<(), ParseError> {
+    let (line_number, line) = next_line(lines, end_line)?;
+    if line == expected {
+        Ok(())
+    } else {
+        Err(ParseError::new(
+            ParseErrorKind::UnknownKey,
+            line_number,
+            1,
+            line,
+        ))
+    }
+}
+
+impl Notes {
+    pub fn parse(text: &str) -> Result<Notes, ParseError> {
+        let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let end_line = text.lines().count() + 1;
+
+        let mut rules = vec![];
+        for (line_number, line) in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            rules.push(Rule::parse(line_number, line)?);
+        }
+
+        expect_line(&mut lines, "your ticket:", end_line)?;
+        let (line_number, line) = next_line(&mut lines, end_line)?;
+        let your_ticket = parse_ticket(line_number, line)?;
+
+        expect_line(&mut lines, "", end_line)?;
+        expect_line(&mut lines, "nearby tickets:", end_line)?;
+        let nearby_tickets = lines
+            .map(|(line_number, line)| parse_ticket(line_number, line))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Notes {
+            rules,
+            your_ticket,
+            nearby_tickets,
+        })
+    }
+
+    fn is_valid_value(&self, value: u64) -> bool {
+        self.rules.iter().any(|rule| rule.is_valid(value))
+    }
+
+    pub fn get_error_rate(&self) -> u64 {
+        self.nearby_tickets
+            .iter()
+            .flatten()
+            .filter(|&&value| !self.is_valid_value(value))
+            .sum()
+    }
+
+    pub fn get_field_order(&self) -> Option<Vec<&str>> {
+        let valid_tickets: Vec<&Vec<u64>> = self
+            .nearby_tickets
+            .iter()
+            .filter(|ticket| ticket.iter().all(|&value| self.is_valid_value(value)))
+            .filter(|ticket| ticket.len() == self.rules.len())
+            .collect();
+
+        let mut candidates: Vec<Vec<usize>> = (0..self.rules.len())
+            .map(|position| {
+                (0..self.rules.len())
+                    .filter(|&rule_index| {
+                        valid_tickets
+                            .iter()
+                            .all(|ticket| self.rules[rule_index].is_valid(ticket[position]))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut order = vec![None; self.rules.len()];
+        while let Some(position) = candidates.iter().position(|rules| rules.len() == 1) {
+            let rule_index = candidates[position][0];
+            order[position] = Some(self.rules[rule_index].name.as_str());
+            for rules in candidates.iter_mut() {
+                rules.retain(|&index| index != rule_index);
+            }
+        }
+        order.into_iter().collect()
+    }
+}
+
+pub struct Day16;
+
+impl Solver for Day16 {
+    type Input = Notes;
+
+    fn parse(text: &str) -> Result<Notes, ParseError> {
+        Notes::parse(text)
+    }
+
+    fn part1(notes: &Notes) -> String {
+        notes.get_error_rate().to_string()
+    }
+
+    fn part2(notes: &Notes) -> String {
+        let order = match notes.get_field_order() {
+            Some(order) => order,
+            None => return String::from("No unique field order"),
+        };
+        order
+            .iter()
+            .zip(notes.your_ticket.iter())
+            .filter(|(name, _)| name.starts_with("departure"))
+            .map(|(_, value)| value)
+            .product::<u64>()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_rate() {
+        let text = "\
+class: 1-3 or 5-7
+row: 6-11 or 33-44
+seat: 13-40 or 45-50
+
+your ticket:
+7,1,14
+
+nearby tickets:
+7,3,47
+40,4,50
+55,2,20
+38,6,12";
+
+        let notes = Notes::parse(text).unwrap();
+        assert_eq!(notes.rules.len(), 3);
+        assert_eq!(notes.your_ticket, [7, 1, 14]);
+        assert_eq!(notes.nearby_tickets.len(), 4);
+        assert_eq!(notes.get_error_rate(), 71);
+    }
+
+    #[test]
+    fn test_field_order() {
+        let text = "\
+class: 0-1 or 4-19
+row: 0-5 or 8-19
+seat: 0-13 or 16-19
+
+your ticket:
+11,12,13
+
+nearby tickets:
+3,9,18
+15,1,5
+5,14,9";
+
+        let notes = Notes::parse(text).unwrap();
+        assert_eq!(notes.get_field_order(), Some(vec!["row", "class", "seat"]));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let text = "\
+class: 1-3 or 5-x
+
+your ticket:
+7,1,14";
+        assert_eq!(
+            Notes::parse(text),
+            Err(ParseError::new(ParseErrorKind::InvalidNumber, 1, 17, "x"))
+        );
+
+        let text = "\
+class: 1-3 or 5-7
+
+your ticket:";
+        assert_eq!(
+            Notes::parse(text),
+            Err(ParseError::new(ParseErrorKind::UnexpectedEnd, 4, 1, ""))
+        );
+    }
+}
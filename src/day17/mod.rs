@@ -0,0 +1,164 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+type Cube = [i32; 4];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PocketDimension {
+    dimensions: usize,
+    active: HashSet<Cube>,
+}
+
+impl PocketDimension {
+    pub fn parse(text: &str) -> Result<HashSet<Cube>, ParseError> {
+        let mut active = HashSet::new();
+        for (y, line) in text.lines().enumerate() {
+            for (x, char) in line.chars().enumerate() {
+                match char {
+                    '#' => {
+                        active.insert([x as i32, y as i32, 0, 0]);
+                    }
+                    '.' => {}
+                    _ => {
+                        return Err(ParseError::new(
+                            ParseErrorKind::InvalidCharacter,
+                            y + 1,
+                            x + 1,
+                            &char.to_string(),
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(active)
+    }
+
+    pub fn new(dimensions: usize, active: HashSet<Cube>) -> PocketDimension {
+        assert!((3..=4).contains(&dimensions));
+        PocketDimension { dimensions, active }
+    }
+
+    fn neighbour_offsets(&self) -> Vec<Cube> {
+        (0..4)
+            .map(|axis| {
+                if axis < self.dimensions {
+                    -1..=1
+                } else {
+                    0..=0
+                }
+            })
+            .multi_cartesian_product()
+            .filter(|offset| offset.iter().any(|&delta| delta != 0))
+            .map(|offset| [offset[0], offset[1], offset[2], offset[3]])
+            .collect()
+    }
+
+    pub fn cycle(&mut self) {
+        let mut neighbour_counts: HashMap<Cube, usize> = HashMap::new();
+        for offset in self.neighbour_offsets() {
+            for cube in &self.active {
+                let neighbour = [
+                    cube[0] + offset[0],
+                    cube[1] + offset[1],
+                    cube[2] + offset[2],
+                    cube[3] + offset[3],
+                ];
+                *neighbour_counts.entry(neighbour).or_default() += 1;
+            }
+        }
+
+        self.active = neighbour_counts
+            .into_iter()
+            .filter(|(cube, count)| *count == 3 || (*count == 2 && self.active.contains(cube)))
+            .map(|(cube, _)| cube)
+            .collect();
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+}
+
+fn boot(dimensions: usize, active: &HashSet<Cube>) -> usize {
+    let mut pocket = PocketDimension::new(dimensions, active.clone());
+    for _ in 0..6 {
+        pocket.cycle();
+    }
+    pocket.active_count()
+}
+
+pub struct Day17;
+
+impl Solver for Day17 {
+    type Input = HashSet<Cube>;
+
+    fn parse(text: &str) -> Result<HashSet<Cube>, ParseError> {
+        PocketDimension::parse(text)
+    }
+
+    fn part1(active: &HashSet<Cube>) -> String {
+        boot(3, active).to_string()
+    }
+
+    fn part2(active: &HashSet<Cube>) -> String {
+        boot(4, active).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+.#.
+..#
+###";
+
+    #[test]
+    fn test_cycle() {
+        let active = PocketDimension::parse(EXAMPLE).unwrap();
+        assert_eq!(active.len(), 5);
+
+        let mut pocket = PocketDimension::new(3, active);
+        pocket.cycle();
+        assert_eq!(pocket.active_count(), 11);
+        pocket.cycle();
+        assert_eq!(pocket.active_count(), 21);
+        pocket.cycle();
+        assert_eq!(pocket.active_count(), 38);
+    }
+
+    #[test]
+    fn test_boot() {
+        let active = PocketDimension::parse(EXAMPLE).unwrap();
+        assert_eq!(boot(3, &active), 112);
+        assert_eq!(boot(4, &active), 848);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            PocketDimension::parse(".#.\n.x#"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 2, 2, "x"))
+        );
+    }
+}
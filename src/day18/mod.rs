@@ -0,0 +1,299 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Token {
+    Number(u64),
+    Add,
+    Multiply,
+    Open,
+    Close,
+}
+
+/// Splits a line into tokens, checking that numbers and operators alternate and that
+/// parentheses balance, so that `evaluate` always receives a well-formed expression.
+fn tokenize(line_number: usize, line: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = vec![];
+    let mut depth = 0;
+    let mut expect_operand = true;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, char)) = chars.next() {
+        let token = match char {
+            ' ' => continue,
+            '+' | '*' if !expect_operand => {
+                expect_operand = true;
+                if char == '+' {
+                    Token::Add
+                } else {
+                    Token::Multiply
+                }
+            }
+            '(' if expect_operand => {
+                depth += 1;
+                Token::Open
+            }
+            ')' if !expect_operand && depth > 0 => {
+                depth -= 1;
+                Token::Close
+            }
+            '0'..='9' if expect_operand => {
+                let mut end = i + 1;
+                while let Some((_, '0'..='9')) = chars.peek() {
+                    chars.next();
+                    end += 1;
+                }
+                let number = line[i..end].parse().map_err(|_| {
+                    ParseError::in_line(
+                        ParseErrorKind::InvalidNumber,
+                        line_number,
+                        line,
+                        &line[i..end],
+                    )
+                })?;
+                expect_operand = false;
+                Token::Number(number)
+            }
+            _ => {
+                return Err(ParseError::in_line(
+                    ParseErrorKind::InvalidCharacter,
+                    line_number,
+                    line,
+                    &line[i..i + char.len_utf8()],
+                ))
+            }
+        };
+        tokens.push(token);
+    }
+    if expect_operand {
+        return Err(ParseError::new(
+            ParseErrorKind::UnexpectedEnd,
+            line_number,
+            line.len() + 1,
+            "",
+        ));
+    }
+    if depth > 0 {
+        return Err(ParseError::new(
+            ParseErrorKind::MissingSeparator(')'),
+            line_number,
+            line.len() + 1,
+            "",
+        ));
+    }
+    Ok(tokens)
+}
+
+pub fn parse(text: &str) -> Result<Vec<Vec<Token>>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| tokenize(i + 1, line))
+        .collect()
+}
+
+fn apply(operator: Token, left: u64, right: u64) -> u64 {
+    match operator {
+        Token::Add => left + right,
+        Token::Multiply => left * right,
+        _ => unreachable!(),
+    }
+}
+
+// Shunting-yard evaluation where `precedence` decides which operator binds
+// tighter; operators with equal precedence are evaluated left to right. The
+// tokens must form a valid expression, as produced by `parse`.
+pub fn evaluate(tokens: &[Token], precedence: fn(Token) -> u8) -> u64 {
+    let mut values = vec![];
+    let mut operators: Vec<Token> = vec![];
+
+    let reduce = |values: &mut Vec<u64>, operator| {
+        let right = values.pop().unwrap();
+        let left = values.pop().unwrap();
+        values.push(apply(operator, left, right));
+    };
+
+    for &token in tokens {
+        match token {
+            Token::Number(number) => values.push(number),
+            Token::Open => operators.push(token),
+            Token::Close => {
+                while let Some(operator) = operators.pop() {
+                    if operator == Token::Open {
+                        break;
+                    }
+                    reduce(&mut values, operator);
+                }
+            }
+            Token::Add | Token::Multiply => {
+                while let Some(&operator) = operators.last() {
+                    if operator == Token::Open || precedence(operator) < precedence(token) {
+                        break;
+                    }
+                    operators.pop();
+                    reduce(&mut values, operator);
+                }
+                operators.push(token);
+            }
+        }
+    }
+    while let Some(operator) = operators.pop() {
+        reduce(&mut values, operator);
+    }
+    values.pop().unwrap()
+}
+
+pub fn same_precedence(_token: Token) -> u8 {
+    0
+}
+
+pub fn addition_first(token: Token) -> u8 {
+    match token {
+        Token::Add => 1,
+        _ => 0,
+    }
+}
+
+pub struct Day18;
+
+impl Solver for Day18 {
+    type Input = Vec<Vec<Token>>;
+
+    fn parse(text: &str) -> Result<Vec<Vec<Token>>, ParseError> {
+        parse(text)
+    }
+
+    fn part1(expressions: &Vec<Vec<Token>>) -> String {
+        expressions
+            .iter()
+            .map(|tokens| evaluate(tokens, same_precedence))
+            .sum::<u64>()
+            .to_string()
+    }
+
+    fn part2(expressions: &Vec<Vec<Token>>) -> String {
+        expressions
+            .iter()
+            .map(|tokens| evaluate(tokens, addition_first))
+            .sum::<u64>()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: [(&str, u64, u64); 6] = [
+        ("1 + 2 * 3 + 4 * 5 + 6", 71, 231),
+        ("1 + (2 * 3) + (4 * (5 + 6))", 51, 51),
+        ("2 * 3 + (4 * 5)", 26, 46),
+        ("5 + (8 * 3 + 9 + 3 * 4 * 3)", 437, 1445),
+        ("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", 12240, 669060),
+        (
+            "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+            13632,
+            23340,
+        ),
+    ];
+
+    #[test]
+    fn test_same_precedence() {
+        for (text, expected, _) in EXAMPLES.iter() {
+            let tokens = tokenize(1, text).unwrap();
+            assert_eq!(evaluate(&tokens, same_precedence), *expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_addition_first() {
+        for (text, _, expected) in EXAMPLES.iter() {
+            let tokens = tokenize(1, text).unwrap();
+            assert_eq!(evaluate(&tokens, addition_first), *expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            parse("1 + 2\n3 - 4"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 2, 3, "-"))
+        );
+        assert_eq!(
+            parse("(1 + 2"),
+            Err(ParseError::new(
+                ParseErrorKind::MissingSeparator(')'),
+                1,
+                7,
+                ""
+            ))
+        );
+        assert_eq!(
+            parse("1 + 2)"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 1, 6, ")"))
+        );
+        assert_eq!(
+            parse("1 + 2\n"),
+            Ok(vec![vec![Token::Number(1), Token::Add, Token::Number(2)]])
+        );
+        assert_eq!(
+            parse("1 + 2\n\n3"),
+            Err(ParseError::new(ParseErrorKind::UnexpectedEnd, 2, 1, ""))
+        );
+        assert_eq!(
+            parse("+"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 1, 1, "+"))
+        );
+        assert_eq!(
+            parse("1 +"),
+            Err(ParseError::new(ParseErrorKind::UnexpectedEnd, 1, 4, ""))
+        );
+        assert_eq!(
+            parse("1 2"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 1, 3, "2"))
+        );
+        assert_eq!(
+            parse("2 * ()"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 1, 6, ")"))
+        );
+        assert_eq!(
+            parse("(1 + 2) (3)"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 1, 9, "("))
+        );
+        assert_eq!(
+            parse("99999999999999999999 + 1"),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidNumber,
+                1,
+                1,
+                "99999999999999999999"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_multi_digit_numbers() {
+        let tokens = tokenize(1, "12 + 3").unwrap();
+        assert_eq!(tokens, [Token::Number(12), Token::Add, Token::Number(3)]);
+        assert_eq!(evaluate(&tokens, same_precedence), 15);
+
+        let tokens = tokenize(1, "(10 + 205) * 3").unwrap();
+        assert_eq!(evaluate(&tokens, same_precedence), 645);
+        assert_eq!(evaluate(&tokens, addition_first), 645);
+    }
+}
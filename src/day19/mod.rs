@@ -0,0 +1,320 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Rule {
+    Char(char),
+    Alternatives(Vec<Vec<usize>>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Messages {
+    rules: BTreeMap<usize, Rule>,
+    messages: Vec<String>,
+}
+
+fn parse_rule(line_number: usize, line: &str) -> Result<(usize, Rule), ParseError> {
+    let (number, rule) = line.split_once(": ").ok_or_else(|| {
+        ParseError::new(ParseErrorKind::MissingSeparator(':'), line_number, 1, line)
+    })?;
+    let number = number.parse().map_err(|_| {
+        ParseError::in_line(ParseErrorKind::InvalidNumber, line_number, line, number)
+    })?;
+
+    if let Some(char) = rule
+        .strip_prefix('"')
+        .and_then(|rule| rule.strip_suffix('"'))
+    {
+        let mut chars = char.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(char), None) => Ok((number, Rule::Char(char))),
+            _ => Err(ParseError::in_line(
+                ParseErrorKind::InvalidLength,
+                line_number,
+                line,
+                rule,
+            )),
+        };
+    }
+
+    let alternatives = rule
+        .split(" | ")
+        .map(|sequence| {
+            sequence
+                .split(' ')
+                .map(|number| {
+                    number.parse().map_err(|_| {
+                        ParseError::in_line(
+                            ParseErrorKind::InvalidNumber,
+                            line_number,
+                            line,
+                            number,
+                        )
+                    })
+                })
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((number, Rule::Alternatives(alternatives)))
+}
+
+impl Messages {
+    pub fn parse(text: &str) -> Result<Messages, ParseError> {
+        let mut lines = text.lines().enumerate();
+        let mut rules = BTreeMap::new();
+        let mut rule_lines = vec![];
+        for (line_index, line) in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            let (number, rule) = parse_rule(line_index + 1, line)?;
+            rules.insert(number, rule);
+            rule_lines.push((line_index + 1, line));
+        }
+
+        for (line_number, line) in rule_lines {
+            let (_, rule) = line.split_once(": ").unwrap();
+            if let Some(missing) = rule
+                .split(' ')
+                .filter_map(|token| token.parse().ok().map(|number| (token, number)))
+                .find(|(_, number)| !rules.contains_key(number))
+            {
+                return Err(ParseError::in_line(
+                    ParseErrorKind::UnknownKey,
+                    line_number,
+                    line,
+                    missing.0,
+                ));
+            }
+        }
+
+        if !rules.contains_key(&0) {
+            return Err(ParseError::new(ParseErrorKind::UnknownKey, 1, 1, "0"));
+        }
+
+        let messages = lines.map(|(_, line)| String::from(line)).collect();
+        Ok(Messages { rules, messages })
+    }
+
+    // Returns every position where a match of `rule` starting at `position` could end.
+    fn match_rule(&self, rule: usize, message: &[char], position: usize) -> Vec<usize> {
+        match self.rules.get(&rule) {
+            None => vec![],
+            Some(Rule::Char(char)) => {
+                if message.get(position) == Some(char) {
+                    vec![position + 1]
+                } else {
+                    vec![]
+                }
+            }
+            Some(Rule::Alternatives(alternatives)) => alternatives
+                .iter()
+                .flat_map(|sequence| {
+                    sequence.iter().fold(vec![position], |positions, &rule| {
+                        positions
+                            .into_iter()
+                            .filter(|&position| position < message.len())
+                            .flat_map(|position| self.match_rule(rule, message, position))
+                            .collect()
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    pub fn matches(&self, message: &str) -> bool {
+        let message: Vec<char> = message.chars().collect();
+        self.match_rule(0, &message, 0).contains(&message.len())
+    }
+
+    pub fn count_matching(&self) -> usize {
+        self.messages
+            .iter()
+            .filter(|message| self.matches(message))
+            .count()
+    }
+
+    pub fn with_loops(&self) -> Messages {
+        let mut messages = self.clone();
+        messages
+            .rules
+            .insert(8, Rule::Alternatives(vec![vec![42], vec![42, 8]]));
+        messages
+            .rules
+            .insert(11, Rule::Alternatives(vec![vec![42, 31], vec![42, 11, 31]]));
+        messages
+    }
+}
+
+pub struct Day19;
+
+impl Solver for Day19 {
+    type Input = Messages;
+
+    fn parse(text: &str) -> Result<Messages, ParseError> {
+        let messages = Messages::parse(text)?;
+        // The looping rules of part 2 are built from rules 42 and 31
+        if let Some(missing) = [42, 31]
+            .iter()
+            .find(|rule| !messages.rules.contains_key(rule))
+        {
+            return Err(ParseError::new(
+                ParseErrorKind::UnknownKey,
+                1,
+                1,
+                &missing.to_string(),
+            ));
+        }
+        Ok(messages)
+    }
+
+    fn part1(messages: &Messages) -> String {
+        messages.count_matching().to_string()
+    }
+
+    fn part2(messages: &Messages) -> String {
+        messages.with_loops().count_matching().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let text = "\
+0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: \"a\"
+5: \"b\"
+
+ababbb
+bababa
+abbbab
+aaabbb
+aaaabbb";
+
+        let messages = Messages::parse(text).unwrap();
+        assert_eq!(messages.rules.len(), 6);
+        assert_eq!(messages.rules[&4], Rule::Char('a'));
+        assert_eq!(messages.messages.len(), 5);
+
+        assert!(messages.matches("ababbb"));
+        assert!(!messages.matches("bababa"));
+        assert!(messages.matches("abbbab"));
+        assert!(!messages.matches("aaabbb"));
+        assert!(!messages.matches("aaaabbb"));
+        assert_eq!(messages.count_matching(), 2);
+    }
+
+    #[test]
+    fn test_loops() {
+        let text = "\
+42: 9 14 | 10 1
+9: 14 27 | 1 26
+10: 23 14 | 28 1
+1: \"a\"
+11: 42 31
+5: 1 14 | 15 1
+19: 14 1 | 14 14
+12: 24 14 | 19 1
+16: 15 1 | 14 14
+31: 14 17 | 1 13
+6: 14 14 | 1 14
+2: 1 24 | 14 4
+0: 8 11
+13: 14 3 | 1 12
+15: 1 | 14
+17: 14 2 | 1 7
+23: 25 1 | 22 14
+28: 16 1
+4: 1 1
+20: 14 14 | 1 15
+3: 5 14 | 16 1
+27: 1 6 | 14 18
+14: \"b\"
+21: 14 1 | 1 14
+25: 1 1 | 1 14
+22: 14 14
+8: 42
+26: 14 22 | 1 20
+18: 15 15
+7: 14 5 | 1 21
+24: 14 1
+
+abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
+bbabbbbaabaabba
+babbbbaabbbbbabbbbbbaabaaabaaa
+aaabbbbbbaaaabaababaabababbabaaabbababababaaa
+bbbbbbbaaaabbbbaaabbabaaa
+bbbababbbbaaaaaaaabbababaaababaabab
+ababaaaaaabaaab
+ababaaaaabbbaba
+baabbaaaabbaaaababbaababb
+abbbbabbbbaaaababbbbbbaaaababb
+aaaaabbaabaaaaababaa
+aaaabbaaaabbaaa
+aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
+babaaabbbaaabaababbaabababaaab
+aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba";
+
+        let messages = Messages::parse(text).unwrap();
+        assert_eq!(messages.count_matching(), 3);
+
+        let messages = messages.with_loops();
+        assert_eq!(messages.count_matching(), 12);
+        assert!(messages.matches("babbbbaabbbbbabbbbbbaabaaabaaa"));
+        assert!(messages.matches("bbabbbbaabaabba"));
+        assert!(!messages.matches("aaaabbaaaabbaaa"));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            Messages::parse("0: 1 x\n1: \"a\""),
+            Err(ParseError::new(ParseErrorKind::InvalidNumber, 1, 6, "x"))
+        );
+        assert_eq!(
+            Messages::parse("0: 1 2\n1: \"a\""),
+            Err(ParseError::new(ParseErrorKind::UnknownKey, 1, 6, "2"))
+        );
+        assert_eq!(
+            Messages::parse("1: \"a\"\n\na"),
+            Err(ParseError::new(ParseErrorKind::UnknownKey, 1, 1, "0"))
+        );
+        assert_eq!(
+            Day19::parse("0: 42\n42: \"a\"\n\na").err(),
+            Some(ParseError::new(ParseErrorKind::UnknownKey, 1, 1, "31"))
+        );
+        assert_eq!(
+            Messages::parse("0: \"ab\""),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidLength,
+                1,
+                4,
+                "\"ab\""
+            ))
+        );
+    }
+}
@@ -0,0 +1,540 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use std::collections::HashSet;
+
+type Pixels = Vec<Vec<bool>>;
+
+const SEA_MONSTER: [&str; 3] = [
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
+
+fn rotate(pixels: &[Vec<bool>]) -> Pixels {
+    let size = pixels.len();
+    (0..size)
+        .map(|y| (0..size).map(|x| pixels[size - 1 - x][y]).collect())
+        .collect()
+}
+
+fn flip(pixels: &[Vec<bool>]) -> Pixels {
+    pixels
+        .iter()
+        .map(|row| row.iter().rev().copied().collect())
+        .collect()
+}
+
+fn orientations(pixels: &[Vec<bool>]) -> Vec<Pixels> {
+    let mut orientations = vec![];
+    let mut current = pixels.to_vec();
+    for _ in 0..4 {
+        orientations.push(flip(&current));
+        current = rotate(&current);
+        orientations.push(current.clone());
+    }
+    orientations
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tile {
+    pub id: u64,
+    pixels: Pixels,
+}
+
+impl Tile {
+    fn parse(first_line: usize, text: &str) -> Result<Tile, ParseError> {
+        let mut lines = text.lines();
+        let header = lines.next().unwrap();
+        let id = header
+            .strip_prefix("Tile ")
+            .and_then(|header| header.strip_suffix(':'))
+            .ok_or_else(|| ParseError::new(ParseErrorKind::UnknownKey, first_line, 1, header))?;
+        let id = id.parse().map_err(|_| {
+            ParseError::in_line(ParseErrorKind::InvalidNumber, first_line, header, id)
+        })?;
+
+        let pixels: Pixels = lines
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, char)| match char {
+                        '#' => Ok(true),
+                        '.' => Ok(false),
+                        _ => Err(ParseError::new(
+                            ParseErrorKind::InvalidCharacter,
+                            first_line + y + 1,
+                            x + 1,
+                            &char.to_string(),
+                        )),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        if pixels.is_empty() {
+            return Err(ParseError::new(
+                ParseErrorKind::UnexpectedEnd,
+                first_line + 1,
+                1,
+                "",
+            ));
+        }
+        if pixels.len() < 2 {
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidLength,
+                first_line + 1,
+                1,
+                text.lines().nth(1).unwrap(),
+            ));
+        }
+        if let Some(y) = pixels.iter().position(|row| row.len() != pixels.len()) {
+            let line = text.lines().nth(y + 1).unwrap();
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidLength,
+                first_line + y + 1,
+                1,
+                line,
+            ));
+        }
+        Ok(Tile { id, pixels })
+    }
+
+    fn borders(pixels: &[Vec<bool>]) -> [Vec<bool>; 4] {
+        [
+            pixels[0].clone(),
+            pixels.iter().map(|row| *row.last().unwrap()).collect(),
+            pixels.last().unwrap().clone(),
+            pixels.iter().map(|row| row[0]).collect(),
+        ]
+    }
+}
+
+fn normalize_border(border: &[bool]) -> Vec<bool> {
+    let reversed: Vec<bool> = border.iter().rev().copied().collect();
+    if reversed.as_slice() < border {
+        reversed
+    } else {
+        border.to_vec()
+    }
+}
+
+pub fn parse(text: &str) -> Result<Vec<Tile>, ParseError> {
+    let mut first_line = 1;
+    let mut tiles = vec![];
+    for block in text.split("\n\n") {
+        let block = block.trim_end_matches('\n');
+        if !block.is_empty() {
+            tiles.push(Tile::parse(first_line, block)?);
+        }
+        first_line += block.lines().count() + 1;
+    }
+    Ok(tiles)
+}
+
+pub struct Puzzle<'a> {
+    tiles: &'a [Tile],
+}
+
+impl Puzzle<'_> {
+    pub fn new(tiles: &[Tile]) -> Puzzle<'_> {
+        Puzzle { tiles }
+    }
+
+    fn is_shared(&self, id: u64, border: &[bool]) -> bool {
+        let border = normalize_border(border);
+        self.tiles.iter().filter(|tile| tile.id != id).any(|tile| {
+            Tile::borders(&tile.pixels)
+                .iter()
+                .any(|other| normalize_border(other) == border)
+        })
+    }
+
+    fn unmatched_sides(&self, tile: &Tile, pixels: &[Vec<bool>]) -> [bool; 4] {
+        let borders = Tile::borders(pixels);
+        let mut unmatched = [false; 4];
+        for (side, border) in borders.iter().enumerate() {
+            unmatched[side] = !self.is_shared(tile.id, border);
+        }
+        unmatched
+    }
+
+    pub fn get_corners(&self) -> Vec<&Tile> {
+        self.tiles
+            .iter()
+            .filter(|tile| {
+                self.unmatched_sides(tile, &tile.pixels)
+                    .iter()
+                    .filter(|&&unmatched| unmatched)
+                    .count()
+                    == 2
+            })
+            .collect()
+    }
+
+    fn find_neighbour(
+        &self,
+        used: &HashSet<u64>,
+        matches: impl Fn(&[Vec<bool>]) -> bool,
+    ) -> Option<(u64, Pixels)> {
+        self.tiles
+            .iter()
+            .filter(|tile| !used.contains(&tile.id))
+            .find_map(|tile| {
+                orientations(&tile.pixels)
+                    .into_iter()
+                    .find(|pixels| matches(pixels))
+                    .map(|pixels| (tile.id, pixels))
+            })
+    }
+
+    pub fn assemble(&self) -> Option<Pixels> {
+        let side = (self.tiles.len() as f64).sqrt() as usize;
+        if side * side != self.tiles.len() {
+            return None;
+        }
+
+        let corner = *self.get_corners().first()?;
+        let corner_pixels = orientations(&corner.pixels).into_iter().find(|pixels| {
+            let unmatched = self.unmatched_sides(corner, pixels);
+            unmatched[0] && unmatched[3]
+        })?;
+
+        let mut used = HashSet::new();
+        used.insert(corner.id);
+        let mut placed: Vec<Vec<Pixels>> = vec![vec![corner_pixels]];
+        for y in 0..side {
+            for x in 0..side {
+                if x == 0 && y == 0 {
+                    continue;
+                }
+                let (id, pixels) = if x == 0 {
+                    let above = Tile::borders(&placed[y - 1][0])[2].clone();
+                    placed.push(vec![]);
+                    self.find_neighbour(&used, |pixels| pixels[0] == above)?
+                } else {
+                    let left = Tile::borders(&placed[y][x - 1])[1].clone();
+                    self.find_neighbour(&used, |pixels| Tile::borders(pixels)[3] == left)?
+                };
+                used.insert(id);
+                placed[y].push(pixels);
+            }
+        }
+
+        let inner = placed[0][0].len() - 2;
+        let mut image = vec![vec![false; side * inner]; side * inner];
+        for (tile_y, row) in placed.iter().enumerate() {
+            for (tile_x, pixels) in row.iter().enumerate() {
+                for y in 0..inner {
+                    for x in 0..inner {
+                        image[tile_y * inner + y][tile_x * inner + x] = pixels[y + 1][x + 1];
+                    }
+                }
+            }
+        }
+        Some(image)
+    }
+}
+
+fn sea_monster_offsets() -> Vec<(usize, usize)> {
+    SEA_MONSTER
+        .iter()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, char)| *char == '#')
+                .map(move |(x, _)| (x, y))
+        })
+        .collect()
+}
+
+pub fn get_water_roughness(image: &[Vec<bool>]) -> usize {
+    let monster = sea_monster_offsets();
+    let width = SEA_MONSTER[0].len();
+    let height = SEA_MONSTER.len();
+    let total = image.iter().flatten().filter(|&&pixel| pixel).count();
+
+    for pixels in orientations(image) {
+        let mut monster_pixels = HashSet::new();
+        // Images smaller than the monster are not scanned at all
+        for y in 0..(pixels.len() + 1).saturating_sub(height) {
+            for x in 0..(pixels.len() + 1).saturating_sub(width) {
+                if monster.iter().all(|(dx, dy)| pixels[y + dy][x + dx]) {
+                    monster_pixels.extend(monster.iter().map(|(dx, dy)| (x + dx, y + dy)));
+                }
+            }
+        }
+        if !monster_pixels.is_empty() {
+            return total - monster_pixels.len();
+        }
+    }
+    total
+}
+
+pub struct Day20;
+
+impl Solver for Day20 {
+    type Input = Vec<Tile>;
+
+    fn parse(text: &str) -> Result<Vec<Tile>, ParseError> {
+        parse(text)
+    }
+
+    fn part1(tiles: &Vec<Tile>) -> String {
+        Puzzle::new(tiles)
+            .get_corners()
+            .iter()
+            .map(|tile| tile.id)
+            .product::<u64>()
+            .to_string()
+    }
+
+    fn part2(tiles: &Vec<Tile>) -> String {
+        match Puzzle::new(tiles).assemble() {
+            Some(image) => get_water_roughness(&image).to_string(),
+            None => String::from("Tiles do not fit into a square image"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Tile 2311:
+..##.#..#.
+##..#.....
+#...##..#.
+####.#...#
+##.##.###.
+##...#.###
+.#.#.#..##
+..#....#..
+###...#.#.
+..###..###
+
+Tile 1951:
+#.##...##.
+#.####...#
+.....#..##
+#...######
+.##.#....#
+.###.#####
+###.##.##.
+.###....#.
+..#.#..#.#
+#...##.#..
+
+Tile 1171:
+####...##.
+#..##.#..#
+##.#..#.#.
+.###.####.
+..###.####
+.##....##.
+.#...####.
+#.##.####.
+####..#...
+.....##...
+
+Tile 1427:
+###.##.#..
+.#..#.##..
+.#.##.#..#
+#.#.#.##.#
+....#...##
+...##..##.
+...#.#####
+.#.####.#.
+..#..###.#
+..##.#..#.
+
+Tile 1489:
+##.#.#....
+..##...#..
+.##..##...
+..#...#...
+#####...#.
+#..#.#.#.#
+...#.#.#..
+##.#...##.
+..##.##.##
+###.##.#..
+
+Tile 2473:
+#....####.
+#..#.##...
+#.##..#...
+######.#.#
+.#...#.#.#
+.#########
+.###.#..#.
+########.#
+##...##.#.
+..###.#.#.
+
+Tile 2971:
+..#.#....#
+#...###...
+#.#.###...
+##.##..#..
+.#####..##
+.#..####.#
+#..#.#..#.
+..####.###
+..#.#.###.
+...#.#.#.#
+
+Tile 2729:
+...#.#.#.#
+####.#....
+..#.#.....
+....#..#.#
+.##..##.#.
+.#.####...
+####.#.#..
+##.####...
+##..#.##..
+#.##...##.
+
+Tile 3079:
+#.#.#####.
+.#..######
+..#.......
+######....
+####.#..#.
+.#...#.##.
+#.#####.##
+..#.###...
+..#.......
+..#.###...
+";
+
+    #[test]
+    fn test_corners() {
+        let tiles = parse(EXAMPLE).unwrap();
+        assert_eq!(tiles.len(), 9);
+        assert_eq!(tiles[0].id, 2311);
+
+        let puzzle = Puzzle::new(&tiles);
+        let mut corners: Vec<u64> = puzzle.get_corners().iter().map(|tile| tile.id).collect();
+        corners.sort_unstable();
+        assert_eq!(corners, [1171, 1951, 2971, 3079]);
+        assert_eq!(Day20::part1(&tiles), "20899048083289");
+    }
+
+    #[test]
+    fn test_water_roughness() {
+        let tiles = parse(EXAMPLE).unwrap();
+        let image = Puzzle::new(&tiles).assemble().unwrap();
+        assert_eq!(image.len(), 24);
+        assert_eq!(get_water_roughness(&image), 273);
+    }
+
+    #[test]
+    fn test_small_tiles() {
+        let text = "\
+Tile 1:
+#.
+..
+
+Tile 2:
+..
+..
+
+Tile 3:
+..
+..
+
+Tile 4:
+..
+..";
+        let tiles = parse(text).unwrap();
+        assert_eq!(Puzzle::new(&tiles).assemble(), Some(vec![]));
+        assert_eq!(Day20::part2(&tiles), "0");
+
+        let text = "\
+Tile 1:
+#.#
+.##
+.##
+
+Tile 2:
+.#.
+..#
+#..
+
+Tile 3:
+.#.
+.#.
+#.#
+
+Tile 4:
+...
+.#.
+..#";
+        let tiles = parse(text).unwrap();
+        assert_eq!(Puzzle::new(&tiles).assemble().unwrap().len(), 2);
+        assert_eq!(Day20::part2(&tiles), "3");
+
+        assert_eq!(get_water_roughness(&vec![vec![true; 5]; 5]), 25);
+    }
+
+    #[test]
+    fn test_orientations() {
+        let pixels = vec![vec![true, false], vec![false, false]];
+        let orientations = orientations(&pixels);
+        assert_eq!(orientations.len(), 8);
+        let unique: HashSet<&Pixels> = orientations.iter().collect();
+        assert_eq!(unique.len(), 4);
+        assert_eq!(rotate(&rotate(&rotate(&rotate(&pixels)))), pixels);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            parse("Tile 12:\n#.\n.#\n\nTile x:\n#.\n.#"),
+            Err(ParseError::new(ParseErrorKind::InvalidNumber, 5, 6, "x"))
+        );
+        assert_eq!(
+            parse("Tile 12:\n#.\n.o"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 3, 2, "o"))
+        );
+        assert_eq!(
+            parse("Tile 12:\n#.\n.##"),
+            Err(ParseError::new(ParseErrorKind::InvalidLength, 3, 1, ".##"))
+        );
+        assert_eq!(
+            parse("Tile 12:\n#"),
+            Err(ParseError::new(ParseErrorKind::InvalidLength, 2, 1, "#"))
+        );
+        assert_eq!(
+            parse("Block 12:\n#.\n.#"),
+            Err(ParseError::new(
+                ParseErrorKind::UnknownKey,
+                1,
+                1,
+                "Block 12:"
+            ))
+        );
+    }
+}
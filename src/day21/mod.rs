@@ -0,0 +1,188 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Food {
+    ingredients: BTreeSet<String>,
+    allergens: BTreeSet<String>,
+}
+
+impl Food {
+    fn parse(line_number: usize, line: &str) -> Result<Food, ParseError> {
+        let (ingredients, allergens) = match line.split_once(" (contains ") {
+            Some((ingredients, allergens)) => {
+                let allergens = allergens.strip_suffix(')').ok_or_else(|| {
+                    ParseError::new(
+                        ParseErrorKind::MissingSeparator(')'),
+                        line_number,
+                        line.len() + 1,
+                        "",
+                    )
+                })?;
+                (
+                    ingredients,
+                    allergens.split(", ").map(String::from).collect(),
+                )
+            }
+            None => (line, BTreeSet::new()),
+        };
+        if let Some(word) = ingredients
+            .split(' ')
+            .find(|word| word.is_empty() || !word.chars().all(|c| c.is_ascii_lowercase()))
+        {
+            return Err(ParseError::in_line(
+                ParseErrorKind::InvalidCharacter,
+                line_number,
+                line,
+                word,
+            ));
+        }
+        Ok(Food {
+            ingredients: ingredients.split(' ').map(String::from).collect(),
+            allergens,
+        })
+    }
+}
+
+pub fn parse(text: &str) -> Result<Vec<Food>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| Food::parse(i + 1, line))
+        .collect()
+}
+
+fn get_candidates(foods: &[Food]) -> BTreeMap<&str, BTreeSet<&str>> {
+    let mut candidates: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for food in foods {
+        let ingredients: BTreeSet<&str> = food.ingredients.iter().map(String::as_str).collect();
+        for allergen in &food.allergens {
+            candidates
+                .entry(allergen)
+                .and_modify(|candidates| candidates.retain(|i| ingredients.contains(i)))
+                .or_insert_with(|| ingredients.clone());
+        }
+    }
+    candidates
+}
+
+pub fn count_safe_ingredients(foods: &[Food]) -> usize {
+    let unsafe_ingredients: BTreeSet<&str> =
+        get_candidates(foods).into_values().flatten().collect();
+    foods
+        .iter()
+        .flat_map(|food| food.ingredients.iter())
+        .filter(|ingredient| !unsafe_ingredients.contains(ingredient.as_str()))
+        .count()
+}
+
+pub fn get_dangerous_ingredients(foods: &[Food]) -> Option<BTreeMap<&str, &str>> {
+    let mut candidates = get_candidates(foods);
+    let mut dangerous = BTreeMap::new();
+    while let Some((&allergen, ingredients)) = candidates
+        .iter()
+        .find(|(_, ingredients)| ingredients.len() == 1)
+    {
+        let ingredient = *ingredients.iter().next().unwrap();
+        dangerous.insert(allergen, ingredient);
+        candidates.remove(allergen);
+        for ingredients in candidates.values_mut() {
+            ingredients.remove(ingredient);
+        }
+    }
+    if candidates.is_empty() {
+        Some(dangerous)
+    } else {
+        None
+    }
+}
+
+pub struct Day21;
+
+impl Solver for Day21 {
+    type Input = Vec<Food>;
+
+    fn parse(text: &str) -> Result<Vec<Food>, ParseError> {
+        parse(text)
+    }
+
+    fn part1(foods: &Vec<Food>) -> String {
+        count_safe_ingredients(foods).to_string()
+    }
+
+    fn part2(foods: &Vec<Food>) -> String {
+        match get_dangerous_ingredients(foods) {
+            Some(dangerous) => dangerous.values().copied().collect::<Vec<_>>().join(","),
+            None => String::from("No unique ingredient per allergen"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
+trh fvjkl sbzzf mxmxvkd (contains dairy)
+sqjhc fvjkl (contains soy)
+sqjhc mxmxvkd sbzzf (contains fish)";
+
+    #[test]
+    fn test_safe_ingredients() {
+        let foods = parse(EXAMPLE).unwrap();
+        assert_eq!(foods.len(), 4);
+        assert_eq!(foods[0].ingredients.len(), 4);
+        assert_eq!(foods[0].allergens.len(), 2);
+        assert_eq!(count_safe_ingredients(&foods), 5);
+    }
+
+    #[test]
+    fn test_dangerous_ingredients() {
+        let foods = parse(EXAMPLE).unwrap();
+        let dangerous = get_dangerous_ingredients(&foods).unwrap();
+        assert_eq!(dangerous["dairy"], "mxmxvkd");
+        assert_eq!(dangerous["fish"], "sqjhc");
+        assert_eq!(dangerous["soy"], "fvjkl");
+        assert_eq!(Day21::part2(&foods), "mxmxvkd,sqjhc,fvjkl");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            parse("mxmxvkd kfcds (contains dairy"),
+            Err(ParseError::new(
+                ParseErrorKind::MissingSeparator(')'),
+                1,
+                30,
+                ""
+            ))
+        );
+        assert_eq!(
+            parse("sqjhc fvjkl\nsqjhc Fvjkl (contains soy)"),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidCharacter,
+                2,
+                7,
+                "Fvjkl"
+            ))
+        );
+    }
+}
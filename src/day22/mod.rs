@@ -0,0 +1,221 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use std::collections::{HashSet, VecDeque};
+
+type Deck = VecDeque<u32>;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+pub fn parse(text: &str) -> Result<(Deck, Deck), ParseError> {
+    let mut decks = vec![];
+    let mut lines = text.lines().enumerate();
+    for header in ["Player 1:", "Player 2:"].iter() {
+        match lines.next() {
+            Some((_, line)) if line == *header => {}
+            Some((line_index, line)) => {
+                return Err(ParseError::new(
+                    ParseErrorKind::UnknownKey,
+                    line_index + 1,
+                    1,
+                    line,
+                ))
+            }
+            None => {
+                return Err(ParseError::new(
+                    ParseErrorKind::UnexpectedEnd,
+                    text.lines().count() + 1,
+                    1,
+                    "",
+                ))
+            }
+        }
+        let mut deck = Deck::new();
+        for (line_index, line) in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            deck.push_back(line.parse().map_err(|_| {
+                ParseError::new(ParseErrorKind::InvalidNumber, line_index + 1, 1, line)
+            })?);
+        }
+        decks.push(deck);
+    }
+    let player2 = decks.pop().unwrap();
+    let player1 = decks.pop().unwrap();
+    Ok((player1, player2))
+}
+
+pub fn play_combat(mut player1: Deck, mut player2: Deck) -> (Player, Deck) {
+    while !player1.is_empty() && !player2.is_empty() {
+        let card1 = player1.pop_front().unwrap();
+        let card2 = player2.pop_front().unwrap();
+        if card1 > card2 {
+            player1.extend([card1, card2].iter());
+        } else {
+            player2.extend([card2, card1].iter());
+        }
+    }
+    if player2.is_empty() {
+        (Player::One, player1)
+    } else {
+        (Player::Two, player2)
+    }
+}
+
+pub fn play_recursive_combat(mut player1: Deck, mut player2: Deck) -> (Player, Deck) {
+    let mut seen = HashSet::new();
+    while !player1.is_empty() && !player2.is_empty() {
+        if !seen.insert((player1.clone(), player2.clone())) {
+            return (Player::One, player1);
+        }
+
+        let card1 = player1.pop_front().unwrap();
+        let card2 = player2.pop_front().unwrap();
+        let winner = if player1.len() >= card1 as usize && player2.len() >= card2 as usize {
+            let sub_deck1 = player1.iter().take(card1 as usize).copied().collect();
+            let sub_deck2 = player2.iter().take(card2 as usize).copied().collect();
+            play_recursive_combat(sub_deck1, sub_deck2).0
+        } else if card1 > card2 {
+            Player::One
+        } else {
+            Player::Two
+        };
+
+        match winner {
+            Player::One => player1.extend([card1, card2].iter()),
+            Player::Two => player2.extend([card2, card1].iter()),
+        }
+    }
+    if player2.is_empty() {
+        (Player::One, player1)
+    } else {
+        (Player::Two, player2)
+    }
+}
+
+pub fn get_score(deck: &Deck) -> u32 {
+    deck.iter()
+        .rev()
+        .enumerate()
+        .map(|(i, card)| (i as u32 + 1) * card)
+        .sum()
+}
+
+pub struct Day22;
+
+impl Solver for Day22 {
+    type Input = (Deck, Deck);
+
+    fn parse(text: &str) -> Result<(Deck, Deck), ParseError> {
+        parse(text)
+    }
+
+    fn part1((player1, player2): &(Deck, Deck)) -> String {
+        let (_, deck) = play_combat(player1.clone(), player2.clone());
+        get_score(&deck).to_string()
+    }
+
+    fn part2((player1, player2): &(Deck, Deck)) -> String {
+        let (_, deck) = play_recursive_combat(player1.clone(), player2.clone());
+        get_score(&deck).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Player 1:
+9
+2
+6
+3
+1
+
+Player 2:
+5
+8
+4
+7
+10";
+
+    #[test]
+    fn test_combat() {
+        let (player1, player2) = parse(EXAMPLE).unwrap();
+        assert_eq!(player1, [9, 2, 6, 3, 1]);
+        assert_eq!(player2, [5, 8, 4, 7, 10]);
+
+        let (winner, deck) = play_combat(player1, player2);
+        assert_eq!(winner, Player::Two);
+        assert_eq!(deck, [3, 2, 10, 6, 8, 5, 9, 4, 7, 1]);
+        assert_eq!(get_score(&deck), 306);
+    }
+
+    #[test]
+    fn test_recursive_combat() {
+        let (player1, player2) = parse(EXAMPLE).unwrap();
+        let (winner, deck) = play_recursive_combat(player1, player2);
+        assert_eq!(winner, Player::Two);
+        assert_eq!(deck, [7, 5, 6, 2, 4, 1, 10, 8, 9, 3]);
+        assert_eq!(get_score(&deck), 291);
+    }
+
+    #[test]
+    fn test_recursive_combat_loop() {
+        let text = "\
+Player 1:
+43
+19
+
+Player 2:
+2
+29
+14";
+        let (player1, player2) = parse(text).unwrap();
+        let (winner, _) = play_recursive_combat(player1, player2);
+        assert_eq!(winner, Player::One);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            parse("Player 1:\n9\nx"),
+            Err(ParseError::new(ParseErrorKind::InvalidNumber, 3, 1, "x"))
+        );
+        assert_eq!(
+            parse("Player 1:\n9\n\nPlayer 3:\n5"),
+            Err(ParseError::new(
+                ParseErrorKind::UnknownKey,
+                4,
+                1,
+                "Player 3:"
+            ))
+        );
+        assert_eq!(
+            parse("Player 1:\n9"),
+            Err(ParseError::new(ParseErrorKind::UnexpectedEnd, 3, 1, ""))
+        );
+    }
+}
@@ -0,0 +1,179 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+
+pub fn parse(text: &str) -> Result<Vec<usize>, ParseError> {
+    let line = text.lines().next().unwrap_or("");
+    let cups: Vec<usize> = line
+        .char_indices()
+        .map(|(i, char)| {
+            char.to_digit(10)
+                .filter(|&digit| digit > 0)
+                .map(|digit| digit as usize)
+                .ok_or_else(|| {
+                    ParseError::new(
+                        ParseErrorKind::InvalidCharacter,
+                        1,
+                        i + 1,
+                        &char.to_string(),
+                    )
+                })
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut sorted = cups.clone();
+    sorted.sort_unstable();
+    if cups.len() < 5 || sorted.iter().enumerate().any(|(i, &cup)| cup != i + 1) {
+        return Err(ParseError::new(ParseErrorKind::InvalidLength, 1, 1, line));
+    }
+    Ok(cups)
+}
+
+// The circle is stored as a successor table: `next[cup]` is the label of the
+// cup clockwise of `cup`. Index 0 is unused.
+pub struct CupCircle {
+    next: Vec<usize>,
+    current: usize,
+}
+
+impl CupCircle {
+    pub fn new(labels: &[usize], total: usize) -> CupCircle {
+        let mut next = vec![0; total + 1];
+        let order: Vec<usize> = labels
+            .iter()
+            .copied()
+            .chain(labels.len() + 1..=total)
+            .collect();
+        for (&cup, &successor) in order.iter().zip(order.iter().cycle().skip(1)) {
+            next[cup] = successor;
+        }
+        CupCircle {
+            next,
+            current: order[0],
+        }
+    }
+
+    pub fn do_move(&mut self) {
+        let max = self.next.len() - 1;
+        let first = self.next[self.current];
+        let second = self.next[first];
+        let third = self.next[second];
+
+        let mut destination = self.current;
+        loop {
+            destination = if destination == 1 {
+                max
+            } else {
+                destination - 1
+            };
+            if destination != first && destination != second && destination != third {
+                break;
+            }
+        }
+
+        self.next[self.current] = self.next[third];
+        self.next[third] = self.next[destination];
+        self.next[destination] = first;
+        self.current = self.next[self.current];
+    }
+
+    pub fn labels_after_one(&self) -> String {
+        let mut labels = String::new();
+        let mut cup = self.next[1];
+        while cup != 1 {
+            labels.push_str(&cup.to_string());
+            cup = self.next[cup];
+        }
+        labels
+    }
+
+    pub fn stars_product(&self) -> u64 {
+        let first = self.next[1];
+        first as u64 * self.next[first] as u64
+    }
+}
+
+pub struct Day23;
+
+impl Solver for Day23 {
+    type Input = Vec<usize>;
+
+    fn parse(text: &str) -> Result<Vec<usize>, ParseError> {
+        parse(text)
+    }
+
+    fn part1(labels: &Vec<usize>) -> String {
+        let mut circle = CupCircle::new(labels, labels.len());
+        for _ in 0..100 {
+            circle.do_move();
+        }
+        circle.labels_after_one()
+    }
+
+    fn part2(labels: &Vec<usize>) -> String {
+        let mut circle = CupCircle::new(labels, 1_000_000);
+        for _ in 0..10_000_000 {
+            circle.do_move();
+        }
+        circle.stars_product().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moves() {
+        let labels = parse("389125467").unwrap();
+
+        let mut circle = CupCircle::new(&labels, labels.len());
+        circle.do_move();
+        assert_eq!(circle.labels_after_one(), "54673289");
+        for _ in 1..10 {
+            circle.do_move();
+        }
+        assert_eq!(circle.labels_after_one(), "92658374");
+
+        assert_eq!(Day23::part1(&labels), "67384529");
+    }
+
+    #[test]
+    fn test_million_cups() {
+        let labels = parse("389125467").unwrap();
+        assert_eq!(Day23::part2(&labels), "149245887792");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            parse("3891x5467"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 1, 5, "x"))
+        );
+        assert_eq!(
+            parse("389125466"),
+            Err(ParseError::new(
+                ParseErrorKind::InvalidLength,
+                1,
+                1,
+                "389125466"
+            ))
+        );
+    }
+}
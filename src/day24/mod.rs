@@ -0,0 +1,176 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+use std::collections::{HashMap, HashSet};
+
+// Axial hex coordinates: east is +q, south-east is +r.
+type Tile = (i32, i32);
+
+const DIRECTIONS: [(&str, Tile); 6] = [
+    ("e", (1, 0)),
+    ("se", (0, 1)),
+    ("sw", (-1, 1)),
+    ("w", (-1, 0)),
+    ("nw", (0, -1)),
+    ("ne", (1, -1)),
+];
+
+fn parse_path(line_number: usize, line: &str) -> Result<Tile, ParseError> {
+    let mut rest = line;
+    let (mut q, mut r) = (0, 0);
+    while !rest.is_empty() {
+        let (direction, (dq, dr)) = DIRECTIONS
+            .iter()
+            .find(|(direction, _)| rest.starts_with(direction))
+            .ok_or_else(|| {
+                ParseError::in_line(
+                    ParseErrorKind::InvalidCharacter,
+                    line_number,
+                    line,
+                    &rest[..rest.chars().next().unwrap().len_utf8()],
+                )
+            })?;
+        q += dq;
+        r += dr;
+        rest = &rest[direction.len()..];
+    }
+    Ok((q, r))
+}
+
+pub fn parse(text: &str) -> Result<Vec<Tile>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| parse_path(i + 1, line))
+        .collect()
+}
+
+pub fn get_black_tiles(paths: &[Tile]) -> HashSet<Tile> {
+    let mut black = HashSet::new();
+    for &tile in paths {
+        if !black.insert(tile) {
+            black.remove(&tile);
+        }
+    }
+    black
+}
+
+pub fn flip_daily(black: &HashSet<Tile>) -> HashSet<Tile> {
+    let mut neighbour_counts: HashMap<Tile, usize> = HashMap::new();
+    for (q, r) in black {
+        for (_, (dq, dr)) in DIRECTIONS.iter() {
+            *neighbour_counts.entry((q + dq, r + dr)).or_default() += 1;
+        }
+    }
+    neighbour_counts
+        .into_iter()
+        .filter(|(tile, count)| {
+            if black.contains(tile) {
+                *count == 1 || *count == 2
+            } else {
+                *count == 2
+            }
+        })
+        .map(|(tile, _)| tile)
+        .collect()
+}
+
+pub struct Day24;
+
+impl Solver for Day24 {
+    type Input = Vec<Tile>;
+
+    fn parse(text: &str) -> Result<Vec<Tile>, ParseError> {
+        parse(text)
+    }
+
+    fn part1(paths: &Vec<Tile>) -> String {
+        get_black_tiles(paths).len().to_string()
+    }
+
+    fn part2(paths: &Vec<Tile>) -> String {
+        let mut black = get_black_tiles(paths);
+        for _ in 0..100 {
+            black = flip_daily(&black);
+        }
+        black.len().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+sesenwnenenewseeswwswswwnenewsewsw
+neeenesenwnwwswnenewnwwsewnenwseswesw
+seswneswswsenwwnwse
+nwnwneseeswswnenewneswwnewseswneseene
+swweswneswnenwsewnwneneseenw
+eesenwseswswnenwswnwnwsewwnwsene
+sewnenenenesenwsewnenwwwse
+wenwwweseeeweswwwnwwe
+wsweesenenewnwwnwsenewsenwwsesesenwne
+neeswseenwwswnwswswnw
+nenwswwsewswnenenewsenwsenwnesesenew
+enewnwewneswsewnwswenweswnenwsenwsw
+sweneswneswneneenwnewenewwneswswnese
+swwesenesewenwneswnwwneseswwne
+enesenwswwswneneswsenwnewswseenwsese
+wnwnesenesenenwwnenwsewesewsesesew
+nenewswnwewswnenesenwnesewesw
+eneswnwswnwsenenwnwnwwseeswneewsenese
+neswnwewnwnwseenwseesewsenwsweewe
+wseweeenwnesenwwwswnew";
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(parse_path(1, "esew").unwrap(), (0, 1));
+        assert_eq!(parse_path(1, "nwwswee").unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_black_tiles() {
+        let paths = parse(EXAMPLE).unwrap();
+        assert_eq!(get_black_tiles(&paths).len(), 10);
+    }
+
+    #[test]
+    fn test_flip_daily() {
+        let paths = parse(EXAMPLE).unwrap();
+        let mut black = get_black_tiles(&paths);
+        black = flip_daily(&black);
+        assert_eq!(black.len(), 15);
+        black = flip_daily(&black);
+        assert_eq!(black.len(), 12);
+        for _ in 2..10 {
+            black = flip_daily(&black);
+        }
+        assert_eq!(black.len(), 37);
+
+        assert_eq!(Day24::part2(&paths), "2208");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            parse("esew\nenxw"),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 2, 2, "n"))
+        );
+    }
+}
@@ -0,0 +1,111 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Solver;
+
+const MODULUS: u64 = 20201227;
+const SUBJECT_NUMBER: u64 = 7;
+
+pub fn parse(text: &str) -> Result<(u64, u64), ParseError> {
+    let mut keys = text.lines().enumerate().map(|(i, line)| {
+        line.parse()
+            .map_err(|_| ParseError::new(ParseErrorKind::InvalidNumber, i + 1, 1, line))
+    });
+    let card = keys
+        .next()
+        .ok_or_else(|| ParseError::new(ParseErrorKind::UnexpectedEnd, 1, 1, ""))??;
+    let door = keys
+        .next()
+        .ok_or_else(|| ParseError::new(ParseErrorKind::UnexpectedEnd, 2, 1, ""))??;
+    Ok((card, door))
+}
+
+pub fn transform(subject_number: u64, loop_size: u64) -> u64 {
+    let mut value = 1;
+    for _ in 0..loop_size {
+        value = value * subject_number % MODULUS;
+    }
+    value
+}
+
+pub fn find_loop_size(public_key: u64) -> Option<u64> {
+    let mut value = 1;
+    for loop_size in 0..MODULUS {
+        if value == public_key {
+            return Some(loop_size);
+        }
+        value = value * SUBJECT_NUMBER % MODULUS;
+    }
+    None
+}
+
+pub fn get_encryption_key(card_public_key: u64, door_public_key: u64) -> Option<u64> {
+    find_loop_size(card_public_key).map(|loop_size| transform(door_public_key, loop_size))
+}
+
+pub struct Day25;
+
+impl Solver for Day25 {
+    type Input = (u64, u64);
+
+    fn parse(text: &str) -> Result<(u64, u64), ParseError> {
+        parse(text)
+    }
+
+    fn part1(&(card, door): &(u64, u64)) -> String {
+        match get_encryption_key(card, door) {
+            Some(key) => key.to_string(),
+            None => String::from("no loop size found"),
+        }
+    }
+
+    fn part2(_keys: &(u64, u64)) -> String {
+        String::from("Merry Christmas!")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loop_size() {
+        assert_eq!(find_loop_size(5764801), Some(8));
+        assert_eq!(find_loop_size(17807724), Some(11));
+        assert_eq!(transform(7, 8), 5764801);
+    }
+
+    #[test]
+    fn test_encryption_key() {
+        let (card, door) = parse("5764801\n17807724").unwrap();
+        assert_eq!(get_encryption_key(card, door), Some(14897079));
+        assert_eq!(transform(card, 11), 14897079);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            parse("5764801\nabc"),
+            Err(ParseError::new(ParseErrorKind::InvalidNumber, 2, 1, "abc"))
+        );
+        assert_eq!(
+            parse("5764801"),
+            Err(ParseError::new(ParseErrorKind::UnexpectedEnd, 2, 1, ""))
+        );
+    }
+}
@@ -0,0 +1,219 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use nom::error::{ErrorKind, FromExternalError};
+use std::fmt;
+use std::num::ParseIntError;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParseErrorKind {
+    UnexpectedEnd,
+    InvalidNumber,
+    InvalidCharacter,
+    InvalidLength,
+    InvalidRotation,
+    MissingSeparator(char),
+    UnknownKey,
+    UnknownInstruction,
+    UnknownLabel,
+    DuplicateLabel,
+    DuplicateKey,
+    Cycle,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ParseErrorKind::InvalidCharacter => write!(f, "invalid character"),
+            ParseErrorKind::InvalidLength => write!(f, "invalid length"),
+            ParseErrorKind::InvalidRotation => write!(f, "invalid rotation"),
+            ParseErrorKind::MissingSeparator(separator) => {
+                write!(f, "missing separator '{}'", separator)
+            }
+            ParseErrorKind::UnknownKey => write!(f, "unknown key"),
+            ParseErrorKind::UnknownInstruction => write!(f, "unknown instruction"),
+            ParseErrorKind::UnknownLabel => write!(f, "unknown label"),
+            ParseErrorKind::DuplicateLabel => write!(f, "duplicate label"),
+            ParseErrorKind::DuplicateKey => write!(f, "duplicate key"),
+            ParseErrorKind::Cycle => write!(f, "cycle"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, line: usize, column: usize, text: &str) -> ParseError {
+        ParseError {
+            kind,
+            line,
+            column,
+            text: String::from(text),
+        }
+    }
+
+    /// Creates an error for `token`, which should be a slice of `line`; the column is derived
+    /// from the position of `token` within `line`.
+    pub fn in_line(
+        kind: ParseErrorKind,
+        line_number: usize,
+        line: &str,
+        token: &str,
+    ) -> ParseError {
+        ParseError::new(kind, line_number, column_of(line, token), token)
+    }
+
+    pub fn at_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+}
+
+/// Returns the 1-based column at which `token` starts in `line`. A token that is not a slice of
+/// `line` is searched for instead, falling back to column 1.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize)
+        .checked_sub(line.as_ptr() as usize)
+        .filter(|offset| offset + token.len() <= line.len())
+        .or_else(|| line.find(token))
+        .unwrap_or(0);
+    offset + 1
+}
+
+/// Error type for nom parsers, which keeps enough information to build a `ParseError`.
+#[derive(Debug, PartialEq)]
+pub struct NomError<'a> {
+    input: &'a str,
+    kind: ParseErrorKind,
+}
+
+impl<'a> NomError<'a> {
+    pub fn new(input: &'a str, kind: ParseErrorKind) -> NomError<'a> {
+        NomError { input, kind }
+    }
+}
+
+impl<'a> nom::error::ParseError<&'a str> for NomError<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        let kind = if input.is_empty() {
+            ParseErrorKind::UnexpectedEnd
+        } else {
+            match kind {
+                ErrorKind::Digit | ErrorKind::MapRes => ParseErrorKind::InvalidNumber,
+                _ => ParseErrorKind::InvalidCharacter,
+            }
+        };
+        NomError { input, kind }
+    }
+
+    fn append(_input: &'a str, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: &'a str, c: char) -> Self {
+        let kind = if input.is_empty() {
+            ParseErrorKind::UnexpectedEnd
+        } else {
+            ParseErrorKind::MissingSeparator(c)
+        };
+        NomError { input, kind }
+    }
+}
+
+impl<'a> FromExternalError<&'a str, ParseIntError> for NomError<'a> {
+    fn from_external_error(input: &'a str, _kind: ErrorKind, _e: ParseIntError) -> Self {
+        NomError {
+            input,
+            kind: ParseErrorKind::InvalidNumber,
+        }
+    }
+}
+
+impl ParseError {
+    /// Converts the error of a nom parser that was run on `line`; the offending text is the word
+    /// at which the parser failed.
+    pub fn from_nom(line: &str, error: nom::Err<NomError>) -> ParseError {
+        match error {
+            nom::Err::Error(error) | nom::Err::Failure(error) => {
+                let rest = error.input.trim_start();
+                let token = match rest
+                    .split(|c: char| c.is_whitespace() || matches!(c, ',' | '.' | ':' | '-'))
+                    .next()
+                {
+                    Some("") | None => rest.get(..1).unwrap_or(""),
+                    Some(token) => token,
+                };
+                let column = line.len() - rest.len() + 1;
+                ParseError::new(error.kind, 1, column, token)
+            }
+            nom::Err::Incomplete(_) => {
+                ParseError::new(ParseErrorKind::UnexpectedEnd, 1, line.len() + 1, "")
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {} '{}'",
+            self.line, self.column, self.kind, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_line() {
+        let line = "nop +0 foo";
+        let error = ParseError::in_line(ParseErrorKind::UnknownInstruction, 3, line, &line[7..]);
+        assert_eq!(error.line, 3);
+        assert_eq!(error.column, 8);
+        assert_eq!(error.text, "foo");
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 8: unknown instruction 'foo'"
+        );
+    }
+
+    #[test]
+    fn test_column_of() {
+        let line = "nop +0 foo";
+        assert_eq!(column_of(line, &line[4..6]), 5);
+        assert_eq!(column_of(line, &line[10..]), 11);
+        assert_eq!(column_of(line, "foo"), 8);
+        assert_eq!(column_of(line, "bar"), 1);
+        assert_eq!(column_of(&line[4..], line), 1);
+
+        let error = ParseError::in_line(ParseErrorKind::InvalidNumber, 1, "acc one", "two");
+        assert_eq!(error.column, 1);
+    }
+}
@@ -0,0 +1,306 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::{ParseError, ParseErrorKind};
+use std::ops::{Index, IndexMut};
+
+/// A cell position as `(x, y)`, where `x` is the column and `y` the row.
+pub type Position = (usize, usize);
+pub type Offset = (isize, isize);
+
+pub const ORTHOGONAL: [Offset; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// All eight directions in reading order.
+pub const DIRECTIONS: [Offset; 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn<F: FnMut(Position) -> T>(width: usize, height: usize, mut cell: F) -> Grid<T> {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut cell)
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a rectangular character map, mapping every character with `cell`.
+    /// Characters for which `cell` returns `None` are reported as invalid.
+    pub fn parse<F: FnMut(char) -> Option<T>>(
+        text: &str,
+        mut cell: F,
+    ) -> Result<Grid<T>, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (y, line) in text.lines().enumerate() {
+            let mut line_width = 0;
+            for (x, char) in line.chars().enumerate() {
+                let value = cell(char).ok_or_else(|| {
+                    ParseError::new(
+                        ParseErrorKind::InvalidCharacter,
+                        y + 1,
+                        x + 1,
+                        &char.to_string(),
+                    )
+                })?;
+                cells.push(value);
+                line_width += 1;
+            }
+            if *width.get_or_insert(line_width) != line_width {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidLength,
+                    y + 1,
+                    1,
+                    line,
+                ));
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Position) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (x, y): Position) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Moves `position` by `offset`, returning `None` when that leaves the grid.
+    pub fn offset(&self, (x, y): Position, (dx, dy): Offset) -> Option<Position> {
+        let x = (x as isize).checked_add(dx)?;
+        let y = (y as isize).checked_add(dy)?;
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            Some((x as usize, y as usize))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_offset(&self, position: Position, offset: Offset) -> Option<&T> {
+        self.offset(position, offset)
+            .and_then(|position| self.get(position))
+    }
+
+    /// Treats the grid as a torus: coordinates outside the grid wrap around.
+    pub fn wrap(&self, x: isize, y: isize) -> Position {
+        (
+            x.rem_euclid(self.width as isize) as usize,
+            y.rem_euclid(self.height as isize) as usize,
+        )
+    }
+
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        &self[self.wrap(x, y)]
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbours(position, &ORTHOGONAL)
+    }
+
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbours(position, &DIRECTIONS)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        position: Position,
+        offsets: &'a [Offset],
+    ) -> impl Iterator<Item = (Position, &'a T)> {
+        offsets
+            .iter()
+            .filter_map(move |&offset| self.offset(position, offset))
+            .map(move |position| (position, &self[position]))
+    }
+
+    /// Iterates the cells seen from `position` in `direction`, excluding the
+    /// start, until the ray leaves the grid.
+    pub fn ray(&self, position: Position, direction: Offset) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            position,
+            direction,
+        }
+    }
+
+    pub fn map<U, F: FnMut(Position, &T) -> U>(&self, mut cell: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self
+                .iter()
+                .map(|(position, value)| cell(position, value))
+                .collect(),
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        self.get(position).expect("position outside of grid")
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        self.get_mut(position).expect("position outside of grid")
+    }
+}
+
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    position: Position,
+    direction: Offset,
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = (Position, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.position = self.grid.offset(self.position, self.direction)?;
+        Some((self.position, &self.grid[self.position]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(text: &str) -> Grid<u32> {
+        Grid::parse(text, |char| char.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456");
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(0, 0)], 1);
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid[(1, 1)], 5);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get((0, 2)), None);
+
+        assert_eq!(
+            Grid::parse("12\n3x", |char| char.to_digit(10)),
+            Err(ParseError::new(ParseErrorKind::InvalidCharacter, 2, 2, "x"))
+        );
+        assert_eq!(
+            Grid::parse("12\n345", |char| char.to_digit(10)),
+            Err(ParseError::new(ParseErrorKind::InvalidLength, 2, 1, "345"))
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        let grid = digits("123\n456");
+        assert_eq!(grid.offset((0, 0), (1, 1)), Some((1, 1)));
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((2, 1), (1, 0)), None);
+        assert_eq!(grid.get_offset((2, 1), (-2, -1)), Some(&1));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789");
+
+        let neighbours: Vec<u32> = grid.neighbours4((1, 1)).map(|(_, &v)| v).collect();
+        assert_eq!(neighbours, [2, 6, 8, 4]);
+        let neighbours: Vec<u32> = grid.neighbours8((1, 1)).map(|(_, &v)| v).collect();
+        assert_eq!(neighbours, [1, 2, 3, 4, 6, 7, 8, 9]);
+        let neighbours: Vec<u32> = grid.neighbours8((0, 0)).map(|(_, &v)| v).collect();
+        assert_eq!(neighbours, [2, 4, 5]);
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = digits("123\n456\n789");
+
+        let ray: Vec<u32> = grid.ray((0, 0), (1, 1)).map(|(_, &v)| v).collect();
+        assert_eq!(ray, [5, 9]);
+        let ray: Vec<Position> = grid.ray((2, 1), (-1, 0)).map(|(p, _)| p).collect();
+        assert_eq!(ray, [(1, 1), (0, 1)]);
+        assert_eq!(grid.ray((0, 0), (0, -1)).count(), 0);
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let grid = digits("123\n456");
+        assert_eq!(grid.get_wrapping(3, 0), &1);
+        assert_eq!(grid.get_wrapping(-1, 0), &3);
+        assert_eq!(grid.get_wrapping(7, -1), &5);
+    }
+
+    #[test]
+    fn test_map() {
+        let grid = digits("12\n34");
+        let doubled = grid.map(|(x, _), &value| value * 2 + x as u32);
+        assert_eq!(doubled, digits("25\n69"));
+        assert_eq!(Grid::from_fn(2, 2, |(x, y)| (y * 2 + x + 1) as u32), grid);
+    }
+}
@@ -0,0 +1,149 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const INPUT_DIR_VARIABLE: &str = "AOC_INPUT_DIR";
+const DEFAULT_INPUT_DIR: &str = "data";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Input {
+    Day(u32),
+    File(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound(PathBuf),
+    File(PathBuf, io::Error),
+    Stdin(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::NotFound(path) => write!(
+                f,
+                "Input file {} not found (pass --input or set {})",
+                path.display(),
+                INPUT_DIR_VARIABLE
+            ),
+            InputError::File(path, error) => {
+                write!(f, "Failed to read input file {}: {}", path.display(), error)
+            }
+            InputError::Stdin(error) => write!(f, "Failed to read input from stdin: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl Input {
+    pub fn from_arg(arg: &str) -> Input {
+        if arg == "-" {
+            Input::Stdin
+        } else {
+            Input::File(PathBuf::from(arg))
+        }
+    }
+
+    pub fn read(&self) -> Result<String, InputError> {
+        match self {
+            Input::Day(day) => read_file(&day_path(day_input_dir(), *day)),
+            Input::File(path) => read_file(path),
+            Input::Stdin => {
+                let mut text = String::new();
+                io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(InputError::Stdin)?;
+                Ok(text)
+            }
+        }
+    }
+}
+
+pub fn day_input_dir() -> PathBuf {
+    env::var_os(INPUT_DIR_VARIABLE)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_INPUT_DIR))
+}
+
+pub fn day_path<P: AsRef<Path>>(dir: P, day: u32) -> PathBuf {
+    dir.as_ref().join(format!("input-day-{:02}.txt", day))
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|error| {
+        if error.kind() == io::ErrorKind::NotFound {
+            InputError::NotFound(path.to_path_buf())
+        } else {
+            InputError::File(path.to_path_buf(), error)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_path() {
+        assert_eq!(
+            day_path("inputs", 3),
+            PathBuf::from("inputs/input-day-03.txt")
+        );
+        assert_eq!(
+            day_path("inputs", 14),
+            PathBuf::from("inputs/input-day-14.txt")
+        );
+    }
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(Input::from_arg("-"), Input::Stdin);
+        assert_eq!(
+            Input::from_arg("my-input.txt"),
+            Input::File(PathBuf::from("my-input.txt"))
+        );
+    }
+
+    #[test]
+    fn test_read_file() {
+        let text = Input::File(PathBuf::from("data/test-day-03.txt"))
+            .read()
+            .unwrap();
+        assert!(text.starts_with("..##......."));
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let error = Input::File(PathBuf::from("data/input-day-99.txt"))
+            .read()
+            .unwrap_err();
+        assert!(matches!(error, InputError::NotFound(_)));
+        assert_eq!(
+            error.to_string(),
+            "Input file data/input-day-99.txt not found (pass --input or set AOC_INPUT_DIR)"
+        );
+    }
+}
@@ -15,10 +15,14 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+pub mod bench;
+pub mod day01;
+pub mod day02;
 pub mod day03;
 pub mod day04;
 pub mod day05;
 pub mod day06;
+pub mod day07;
 pub mod day08;
 pub mod day09;
 pub mod day10;
@@ -27,3 +31,28 @@ pub mod day12;
 pub mod day13;
 pub mod day14;
 pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod runner;
+pub mod verify;
+
+use crate::error::ParseError;
+
+pub trait Solver {
+    type Input;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> String;
+    fn part2(input: &Self::Input) -> String;
+}
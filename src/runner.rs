@@ -0,0 +1,367 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::bench;
+use crate::bench::DayBench;
+use crate::error::ParseError;
+use crate::input;
+use crate::input::Input;
+use crate::verify;
+use crate::*;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn parse(text: &str) -> Option<Part> {
+        match text {
+            "1" => Some(Part::One),
+            "2" => Some(Part::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(&self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+pub struct Day {
+    pub number: u32,
+    solve: fn(&str, &[Part]) -> Result<Vec<String>, ParseError>,
+    bench: fn(u32, &str, usize) -> Result<DayBench, ParseError>,
+}
+
+impl Day {
+    const fn new<S: Solver>(number: u32) -> Day {
+        Day {
+            number,
+            solve: solve::<S>,
+            bench: bench::bench::<S>,
+        }
+    }
+
+    pub fn solve(&self, text: &str, parts: &[Part]) -> Result<Vec<String>, ParseError> {
+        (self.solve)(text, parts)
+    }
+
+    pub fn bench(&self, text: &str, iterations: usize) -> Result<DayBench, ParseError> {
+        (self.bench)(self.number, text, iterations)
+    }
+}
+
+fn solve<S: Solver>(text: &str, parts: &[Part]) -> Result<Vec<String>, ParseError> {
+    let input = S::parse(text)?;
+    Ok(parts
+        .iter()
+        .map(|part| match part {
+            Part::One => S::part1(&input),
+            Part::Two => S::part2(&input),
+        })
+        .collect())
+}
+
+pub const DAYS: [Day; 25] = [
+    Day::new::<day01::Day01>(1),
+    Day::new::<day02::Day02>(2),
+    Day::new::<day03::Day03>(3),
+    Day::new::<day04::Day04>(4),
+    Day::new::<day05::Day05>(5),
+    Day::new::<day06::Day06>(6),
+    Day::new::<day07::Day07>(7),
+    Day::new::<day08::Day08>(8),
+    Day::new::<day09::Day09>(9),
+    Day::new::<day10::Day10>(10),
+    Day::new::<day11::Day11>(11),
+    Day::new::<day12::Day12>(12),
+    Day::new::<day13::Day13>(13),
+    Day::new::<day14::Day14>(14),
+    Day::new::<day15::Day15>(15),
+    Day::new::<day16::Day16>(16),
+    Day::new::<day17::Day17>(17),
+    Day::new::<day18::Day18>(18),
+    Day::new::<day19::Day19>(19),
+    Day::new::<day20::Day20>(20),
+    Day::new::<day21::Day21>(21),
+    Day::new::<day22::Day22>(22),
+    Day::new::<day23::Day23>(23),
+    Day::new::<day24::Day24>(24),
+    Day::new::<day25::Day25>(25),
+];
+
+pub const DEFAULT_BENCH_ITERATIONS: usize = 5;
+
+pub fn find_day(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Options {
+    pub day: Option<u32>,
+    pub all: bool,
+    pub part: Option<Part>,
+    pub input: Option<Input>,
+    pub iterations: Option<usize>,
+    pub json: bool,
+}
+
+impl Options {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--all" => options.all = true,
+                "--part" => {
+                    let value = args.next().ok_or("Missing value for --part")?;
+                    let part = Part::parse(&value).ok_or(format!("Invalid part: {}", value))?;
+                    options.part = Some(part);
+                }
+                "--input" => {
+                    let value = args.next().ok_or("Missing value for --input")?;
+                    options.input = Some(Input::from_arg(&value));
+                }
+                "--iterations" => {
+                    let value = args.next().ok_or("Missing value for --iterations")?;
+                    let iterations = value
+                        .parse()
+                        .ok()
+                        .filter(|&iterations| iterations > 0)
+                        .ok_or(format!("Invalid number of iterations: {}", value))?;
+                    options.iterations = Some(iterations);
+                }
+                "--json" => options.json = true,
+                _ => {
+                    let day = arg.parse().map_err(|_| format!("Invalid day: {}", arg))?;
+                    options.day = Some(day);
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+pub fn read_input(day: &Day, options: &Options) -> Result<String, String> {
+    options
+        .input
+        .clone()
+        .unwrap_or(Input::Day(day.number))
+        .read()
+        .map_err(|error| error.to_string())
+}
+
+fn selected_days(options: &Options) -> Result<Vec<&'static Day>, String> {
+    if options.all {
+        if options.input.is_some() {
+            return Err(String::from("--input can not be combined with --all"));
+        }
+        let input_dir = input::day_input_dir();
+        Ok(DAYS
+            .iter()
+            .filter(|day| {
+                let path = input::day_path(&input_dir, day.number);
+                let exists = path.exists();
+                if !exists {
+                    eprintln!("Skipping day {}: {} not found", day.number, path.display());
+                }
+                exists
+            })
+            .collect())
+    } else {
+        let number = options.day.ok_or("Missing day number")?;
+        let day = find_day(number).ok_or(format!("Day {} is not implemented", number))?;
+        Ok(vec![day])
+    }
+}
+
+pub fn run_day(day: &Day, options: &Options) -> Result<(), String> {
+    let text = read_input(day, options)?;
+
+    let parts = match options.part {
+        Some(part) => vec![part],
+        None => vec![Part::One, Part::Two],
+    };
+
+    let answers = day
+        .solve(&text, &parts)
+        .map_err(|error| format!("Invalid input for day {}: {}", day.number, error))?;
+    for (part, answer) in parts.iter().zip(answers) {
+        println!("Day {} part {}: {}", day.number, part.number(), answer);
+    }
+    Ok(())
+}
+
+pub fn run(options: &Options) -> Result<(), String> {
+    selected_days(options)?
+        .into_iter()
+        .try_for_each(|day| run_day(day, options))
+}
+
+pub fn bench(options: &Options) -> Result<(), String> {
+    let iterations = options.iterations.unwrap_or(DEFAULT_BENCH_ITERATIONS);
+    let results = selected_days(options)?
+        .into_iter()
+        .map(|day| {
+            let text = read_input(day, options)?;
+            day.bench(&text, iterations)
+                .map_err(|error| format!("Invalid input for day {}: {}", day.number, error))
+        })
+        .collect::<Result<Vec<DayBench>, String>>()?;
+
+    if options.json {
+        println!("{}", bench::format_json(&results));
+    } else {
+        print!("{}", bench::format_table(&results));
+    }
+    Ok(())
+}
+
+pub fn verify(options: &Options) -> Result<(), String> {
+    let answers = verify::load_answers(&verify::answers_path())?;
+    let days = match options.day {
+        Some(number) => vec![number],
+        None => answers.keys().copied().collect(),
+    };
+
+    let mut checks = vec![];
+    for number in days {
+        let day = find_day(number).ok_or(format!("Day {} is not implemented", number))?;
+        let day_answers = answers
+            .get(&number)
+            .ok_or(format!("No known answers for day {}", number))?;
+        let text = read_input(day, options)?;
+        checks.extend(
+            verify::check_day(day, &text, day_answers)
+                .map_err(|error| format!("Invalid input for day {}: {}", number, error))?,
+        );
+    }
+
+    for check in checks.iter() {
+        if check.passed() {
+            println!("Day {} part {}: ok", check.day, check.part.number());
+        } else {
+            println!(
+                "Day {} part {}: MISMATCH expected {}, got {}",
+                check.day,
+                check.part.number(),
+                check.expected,
+                check.actual
+            );
+        }
+    }
+
+    let failed = checks.iter().filter(|check| !check.passed()).count();
+    if failed > 0 {
+        Err(format!(
+            "{} of {} answers do not match",
+            failed,
+            checks.len()
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn day_main(number: u32) {
+    let result = Options::parse(std::env::args().skip(1)).and_then(|mut options| {
+        options.day = Some(number);
+        run(&options)
+    });
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = Options::parse(args("11 --part 2 --input path")).unwrap();
+        assert_eq!(options.day, Some(11));
+        assert!(!options.all);
+        assert_eq!(options.part, Some(Part::Two));
+        assert_eq!(options.input, Some(Input::File(PathBuf::from("path"))));
+
+        let options = Options::parse(args("11 --input -")).unwrap();
+        assert_eq!(options.input, Some(Input::Stdin));
+
+        let options = Options::parse(args("--all")).unwrap();
+        assert_eq!(options.day, None);
+        assert!(options.all);
+
+        let options = Options::parse(args("3 --iterations 10 --json")).unwrap();
+        assert_eq!(options.iterations, Some(10));
+        assert!(options.json);
+        assert!(Options::parse(args("3 --iterations 0")).is_err());
+
+        assert!(Options::parse(args("--part 3")).is_err());
+        assert!(Options::parse(args("eleven")).is_err());
+    }
+
+    #[test]
+    fn test_find_day() {
+        assert_eq!(find_day(11).unwrap().number, 11);
+        assert!(find_day(26).is_none());
+    }
+
+    #[test]
+    fn test_solve() {
+        let day = find_day(10).unwrap();
+        let text = "\
+16
+10
+15
+5
+1
+11
+7
+19
+6
+12
+4";
+        assert_eq!(
+            day.solve(text, &[Part::One, Part::Two]).unwrap(),
+            ["35", "8"]
+        );
+        assert_eq!(day.solve(text, &[Part::Two]).unwrap(), ["8"]);
+        assert!(day.solve("16\nten", &[Part::One]).is_err());
+    }
+
+    #[test]
+    fn test_bench() {
+        let day = find_day(10).unwrap();
+        let result = day.bench("16\n10\n15", 3).unwrap();
+        assert_eq!(result.day, 10);
+        assert_eq!(result.iterations, 3);
+        assert!(result.parse.min_ns <= result.parse.median_ns);
+        assert!(result.parse.median_ns <= result.parse.max_ns);
+    }
+}
@@ -0,0 +1,143 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::error::ParseError;
+use crate::input::day_input_dir;
+use crate::runner::{Day, Part};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct DayAnswers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+pub type Answers = BTreeMap<u32, DayAnswers>;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Check {
+    pub day: u32,
+    pub part: Part,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        self.expected == self.actual
+    }
+}
+
+pub fn answers_path() -> PathBuf {
+    day_input_dir().join("answers.toml")
+}
+
+pub fn parse_answers(text: &str) -> Result<Answers, String> {
+    let table: BTreeMap<String, DayAnswers> =
+        toml::from_str(text).map_err(|error| error.to_string())?;
+    table
+        .into_iter()
+        .map(|(key, answers)| {
+            key.strip_prefix("day")
+                .and_then(|number| number.parse().ok())
+                .map(|day| (day, answers))
+                .ok_or(format!("Invalid day key: {}", key))
+        })
+        .collect()
+}
+
+pub fn load_answers(path: &Path) -> Result<Answers, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+    parse_answers(&text).map_err(|error| format!("Invalid {}: {}", path.display(), error))
+}
+
+pub fn check_day(day: &Day, text: &str, answers: &DayAnswers) -> Result<Vec<Check>, ParseError> {
+    let expected: Vec<(Part, &String)> = [(Part::One, &answers.part1), (Part::Two, &answers.part2)]
+        .iter()
+        .filter_map(|(part, answer)| answer.as_ref().map(|answer| (*part, answer)))
+        .collect();
+    let parts: Vec<Part> = expected.iter().map(|(part, _)| *part).collect();
+
+    let actual = day.solve(text, &parts)?;
+
+    Ok(expected
+        .into_iter()
+        .zip(actual)
+        .map(|((part, expected), actual)| Check {
+            day: day.number,
+            part,
+            expected: expected.clone(),
+            actual,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::find_day;
+
+    #[test]
+    fn test_parse_answers() {
+        let text = r#"
+[day03]
+part1 = "7"
+part2 = "336"
+
+[day10]
+part2 = "8"
+"#;
+        let answers = parse_answers(text).unwrap();
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[&3].part1.as_deref(), Some("7"));
+        assert_eq!(answers[&10].part1, None);
+        assert_eq!(answers[&10].part2.as_deref(), Some("8"));
+
+        assert!(parse_answers("[three]\npart1 = \"7\"").is_err());
+    }
+
+    #[test]
+    fn test_check_day() {
+        let day = find_day(10).unwrap();
+        let text = "\
+16
+10
+15
+5
+1
+11
+7
+19
+6
+12
+4";
+        let answers = DayAnswers {
+            part1: Some(String::from("35")),
+            part2: Some(String::from("9")),
+        };
+        let checks = check_day(day, text, &answers).unwrap();
+        assert_eq!(checks.len(), 2);
+        assert!(checks[0].passed());
+        assert_eq!(checks[1].part, Part::Two);
+        assert_eq!(checks[1].actual, "8");
+        assert!(!checks[1].passed());
+    }
+}
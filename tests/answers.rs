@@ -0,0 +1,42 @@
+/* Copyright (C) 2020 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use advent_of_code_2020::input::{day_path, Input};
+use advent_of_code_2020::runner::find_day;
+use advent_of_code_2020::verify;
+use std::path::Path;
+
+#[test]
+fn test_known_answers() {
+    let answers = verify::load_answers(Path::new("data/answers.toml")).unwrap();
+    assert!(!answers.is_empty());
+
+    for (&number, day_answers) in answers.iter() {
+        let day = find_day(number).unwrap();
+        let text = Input::File(day_path("data", number)).read().unwrap();
+        for check in verify::check_day(day, &text, day_answers).unwrap() {
+            assert!(
+                check.passed(),
+                "day {} part {}: expected {}, got {}",
+                check.day,
+                check.part.number(),
+                check.expected,
+                check.actual
+            );
+        }
+    }
+}